@@ -0,0 +1,241 @@
+// the slow, rarely changing fields (process spawns, pci.ids, the network)
+// saved between runs for CollectOptions::cache. The file is the json of
+//
+//     {"gpu": {"time": <unix secs>, "value": <same as in to_value>}, ...}
+//
+// and every entry expires on its own, so a run that only shows some fields
+// neither drops nor renews the others
+use std::{env, fs, net::IpAddr, path::Path};
+
+use crate::{
+    Cpu, Desktop, Display, Gpu, PACKAGE_MANAGERS, RamHardware, SystemInfo, get_time,
+    output::{Value, parse_json},
+};
+
+pub struct CacheOptions {
+    pub path: String,
+    // entries older than this are collected again
+    pub ttl_secs: u64,
+    // ignore what's there and collect (and save) everything again
+    pub refresh: bool,
+}
+
+// field key and the key of its value in SystemInfo::to_value
+static CACHED: [(&str, &str); 10] = [
+    ("host", "host"),
+    ("host_type", "host_type"),
+    ("cpu", "cpu"),
+    ("gpu", "gpus"),
+    ("packages", "packages"),
+    ("shell", "shell"),
+    ("wm", "wm"),
+    ("ram_type", "ram"),
+    ("resolution", "displays"),
+    ("public_ip", "public_ip"),
+];
+
+// $XDG_CACHE_HOME/rfetch/cache, falling back to ~/.cache/rfetch/cache
+pub fn default_cache_path() -> Option<String> {
+    let dir = match env::var("XDG_CACHE_HOME") {
+        Ok(dir) if !dir.is_empty() => dir,
+        _ => format!("{}/.cache", env::var("HOME").ok()?),
+    };
+    Some(format!("{}/rfetch/cache", dir))
+}
+
+// fresh entries of the cache file: field key, when it was collected, value
+#[derive(Default)]
+pub(crate) struct Cache {
+    entries: Vec<(String, u64, Value)>,
+}
+
+fn opt_string(value: &Value) -> Option<Option<String>> {
+    match value {
+        Value::Null => Some(None),
+        value => Some(Some(value.as_str()?.to_string())),
+    }
+}
+
+fn opt_number(value: Option<&Value>) -> Option<u64> {
+    value.and_then(Value::as_u64)
+}
+
+impl Cache {
+    // a missing, unreadable or malformed file is just an empty cache
+    pub(crate) fn load(options: &CacheOptions) -> Cache {
+        if options.refresh {
+            return Cache::default();
+        }
+        let Some(Value::Object(fields)) = fs::read_to_string(&options.path)
+            .ok()
+            .and_then(|text| parse_json(&text))
+        else {
+            return Cache::default();
+        };
+        let now = get_time().unwrap_or(0);
+        let entries = fields
+            .into_iter()
+            .filter_map(|(key, entry)| {
+                let time = entry.get("time")?.as_u64()?;
+                let Value::Object(mut parts) = entry else {
+                    return None;
+                };
+                let value = parts.iter().position(|(k, _)| k == "value")?;
+                let (_, value) = parts.swap_remove(value);
+                (now.saturating_sub(time) < options.ttl_secs).then_some((key, time, value))
+            })
+            .collect();
+        Cache { entries }
+    }
+
+    fn value(&self, key: &str) -> Option<&Value> {
+        self.entries
+            .iter()
+            .find(|(k, _, _)| k == key)
+            .map(|(_, _, value)| value)
+    }
+
+    pub(crate) fn host(&self) -> Option<Option<String>> {
+        opt_string(self.value("host")?)
+    }
+
+    pub(crate) fn host_type(&self) -> Option<String> {
+        Some(self.value("host_type")?.as_str()?.to_string())
+    }
+
+    pub(crate) fn cpu(&self) -> Option<Cpu> {
+        let cpu = self.value("cpu")?;
+        Some(Cpu {
+            model: cpu.get("model")?.as_str()?.to_string(),
+            cores: opt_number(cpu.get("cores")).map(|n| n as usize),
+            threads: opt_number(cpu.get("threads")).map(|n| n as usize),
+            sockets: opt_number(cpu.get("sockets")).map(|n| n as usize),
+            max_freq_mhz: opt_number(cpu.get("max_freq_mhz")),
+        })
+    }
+
+    pub(crate) fn gpus(&self) -> Option<Vec<Gpu>> {
+        self.value("gpu")?
+            .as_list()?
+            .iter()
+            .map(|gpu| {
+                Some(Gpu {
+                    name: gpu.get("name")?.as_str()?.to_string(),
+                    driver: opt_string(gpu.get("driver")?)?,
+                    vram: opt_number(gpu.get("vram")),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn packages(&self) -> Option<Vec<(&'static str, usize)>> {
+        let Value::Object(counts) = self.value("packages")? else {
+            return None;
+        };
+        counts
+            .iter()
+            .map(|(manager, n)| {
+                let manager = PACKAGE_MANAGERS.iter().find(|m| *m == manager)?;
+                Some((*manager, n.as_u64()? as usize))
+            })
+            .collect()
+    }
+
+    pub(crate) fn shell(&self) -> Option<Option<String>> {
+        opt_string(self.value("shell")?)
+    }
+
+    pub(crate) fn wm(&self) -> Option<Option<Desktop>> {
+        let wm = self.value("wm")?;
+        if let Value::Null = wm {
+            return Some(None);
+        }
+        Some(Some(Desktop {
+            name: wm.get("name")?.as_str()?.to_string(),
+            version: opt_string(wm.get("version")?)?,
+            is_de: wm.get("kind")?.as_str()? == "de",
+        }))
+    }
+
+    pub(crate) fn ram(&self) -> Option<Option<RamHardware>> {
+        let ram = self.value("ram_type")?;
+        if let Value::Null = ram {
+            return Some(None);
+        }
+        Some(Some(RamHardware {
+            kind: ram.get("kind")?.as_str()?.to_string(),
+            speed: opt_number(ram.get("speed")).map(|n| n as u32),
+            populated: ram.get("populated")?.as_u64()? as usize,
+            slots: ram.get("slots")?.as_u64()? as usize,
+            size: opt_number(ram.get("size")),
+        }))
+    }
+
+    pub(crate) fn displays(&self) -> Option<Vec<Display>> {
+        self.value("resolution")?
+            .as_list()?
+            .iter()
+            .map(|display| {
+                Some(Display {
+                    mode: display.get("mode")?.as_str()?.to_string(),
+                    refresh: opt_number(display.get("refresh")).map(|n| n as u32),
+                })
+            })
+            .collect()
+    }
+
+    pub(crate) fn public_ip(&self) -> Option<Option<IpAddr>> {
+        Some(self.value("public_ip")?.as_str()?.parse().ok())
+    }
+
+    // keeps the entries that were used as they are and adds what wanted says
+    // was collected this run; failed lookups (null) aren't saved, so they
+    // are retried next time
+    pub(crate) fn store(
+        self,
+        options: &CacheOptions,
+        info: &SystemInfo,
+        wanted: impl Fn(&str) -> bool,
+    ) {
+        let Value::Object(mut values) = info.to_value() else {
+            return;
+        };
+        let now = get_time().unwrap_or(0);
+        let mut entries = self.entries;
+        for (key, json_key) in CACHED {
+            if entries.iter().any(|(k, _, _)| k == key) || !wanted(key) {
+                continue;
+            }
+            let Some(index) = values.iter().position(|(k, _)| k == json_key) else {
+                continue;
+            };
+            let (_, value) = values.swap_remove(index);
+            if !matches!(value, Value::Null) {
+                entries.push((key.to_string(), now, value));
+            }
+        }
+
+        let json = Value::Object(
+            entries
+                .into_iter()
+                .map(|(key, time, value)| {
+                    let entry = vec![
+                        ("time".to_string(), Value::Number(time)),
+                        ("value".to_string(), value),
+                    ];
+                    (key, Value::Object(entry))
+                })
+                .collect(),
+        )
+        .to_json();
+        if let Some(dir) = Path::new(&options.path).parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        // written next to it and renamed over it, so a run that loads while
+        // another one saves sees the old file or the new one, never half
+        let tmp = format!("{}.{}.tmp", options.path, std::process::id());
+        if fs::write(&tmp, json).is_err() || fs::rename(&tmp, &options.path).is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+    }
+}
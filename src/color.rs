@@ -0,0 +1,140 @@
+use std::env;
+
+// "#rrggbb" or "r,g,b"
+pub fn parse_rgb(s: &str) -> Option<(u8, u8, u8)> {
+    if let Some(hex) = s.strip_prefix('#') {
+        if hex.len() != 6 || !hex.is_ascii() {
+            return None;
+        }
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    let parts: Vec<&str> = s.split(',').collect();
+    if parts.len() != 3 {
+        return None;
+    }
+    let channel = |i: usize| parts[i].trim().parse::<u8>().ok();
+    Some((channel(0)?, channel(1)?, channel(2)?))
+}
+
+pub fn supports_truecolor() -> bool {
+    matches!(
+        env::var("COLORTERM").as_deref(),
+        Ok("truecolor") | Ok("24bit")
+    )
+}
+
+// closest entry in the 6x6x6 cube or the grayscale ramp of the 256 palette
+pub fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let levels = [0u8, 95, 135, 175, 215, 255];
+    let nearest_level = |v: u8| {
+        (0..6)
+            .min_by_key(|&i| (levels[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (nearest_level(r), nearest_level(g), nearest_level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_index = ((avg.saturating_sub(8)) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_index as i32;
+
+    let dist = |cr: i32, cg: i32, cb: i32| {
+        (r as i32 - cr).pow(2) + (g as i32 - cg).pow(2) + (b as i32 - cb).pow(2)
+    };
+    let cube_dist = dist(levels[ri] as i32, levels[gi] as i32, levels[bi] as i32);
+    let gray_dist = dist(gray_level, gray_level, gray_level);
+
+    if gray_dist < cube_dist {
+        232 + gray_index
+    } else {
+        cube
+    }
+}
+
+// SGR parameters for a foreground rgb color, degrading to the 256 palette
+// when the terminal doesn't advertise truecolor
+pub fn rgb_sgr(r: u8, g: u8, b: u8) -> String {
+    if supports_truecolor() {
+        format!("38;2;{};{};{}", r, g, b)
+    } else {
+        format!("38;5;{}", rgb_to_ansi256(r, g, b))
+    }
+}
+
+static COLOR_NAMES: [&str; 8] = [
+    "black", "red", "green", "yellow", "blue", "magenta", "cyan", "white",
+];
+
+fn named_sgr(name: &str) -> Option<String> {
+    let (base, name) = match name.strip_prefix("bright_") {
+        Some(name) => (90, name),
+        None => (30, name),
+    };
+    let index = COLOR_NAMES.iter().position(|&n| n == name)?;
+    Some((base + index).to_string())
+}
+
+// numeric SGR parameters, with the 38/48 extended forms checked for the right
+// number of arguments
+fn is_valid_sgr(sgr: &str) -> bool {
+    let Some(codes) = sgr
+        .split(';')
+        .map(|part| part.parse::<u8>().ok())
+        .collect::<Option<Vec<u8>>>()
+    else {
+        return false;
+    };
+
+    let mut i = 0;
+    while i < codes.len() {
+        if codes[i] == 38 || codes[i] == 48 {
+            match codes.get(i + 1) {
+                Some(5) if i + 2 < codes.len() => i += 3,
+                Some(2) if i + 4 < codes.len() => i += 5,
+                _ => return false,
+            }
+        } else {
+            i += 1;
+        }
+    }
+    true
+}
+
+// turns a --color argument into SGR parameters: rgb forms are converted,
+// names map to the basic 16 colors and numeric codes are validated
+pub fn parse_color(arg: &str) -> Result<String, String> {
+    if let Some((r, g, b)) = parse_rgb(arg) {
+        return Ok(rgb_sgr(r, g, b));
+    }
+    if let Some(sgr) = named_sgr(&arg.to_lowercase()) {
+        return Ok(sgr);
+    }
+    if is_valid_sgr(arg) {
+        return Ok(arg.to_string());
+    }
+    Err(format!(
+        "invalid color '{}' (expected an ANSI code like 1;36 or 38;5;205, #rrggbb, r,g,b or a name like red/bright_blue)",
+        arg
+    ))
+}
+
+// one SGR code per line, linearly interpolated from start to end
+pub fn gradient_sgr(start: (u8, u8, u8), end: (u8, u8, u8), lines: usize) -> Vec<String> {
+    let lerp = |a: u8, b: u8, t: f64| (a as f64 + (b as f64 - a as f64) * t).round() as u8;
+    (0..lines)
+        .map(|i| {
+            let t = if lines > 1 {
+                i as f64 / (lines - 1) as f64
+            } else {
+                0.0
+            };
+            rgb_sgr(
+                lerp(start.0, end.0, t),
+                lerp(start.1, end.1, t),
+                lerp(start.2, end.2, t),
+            )
+        })
+        .collect()
+}
@@ -0,0 +1,221 @@
+// a config file is either plain ascii art, or a header of `key: value` lines
+// followed by a `---` line and then the art:
+//
+//     order: title, cpu, memory
+//     memory: "RAM"
+//     ---
+//     <ascii art>
+//
+// the keys:
+//
+//     order                fields to show first
+//     percent_only         usage fields as just a percentage
+//     hide_disabled_swap   no swap line when there is no swap
+//     kernel_short         the kernel without its sysname
+//     precision            like --precision
+//     trim_zeros           like --trim-zeros
+//     date_format          strftime format of the date field
+//     color                like --color
+//     spacing              like --spacing
+//     separator_char       the separator line's pattern (or just `separator`)
+//     separator_length     and its length
+//     label_color          color of the field labels
+//     cache                like --cache
+//     colors               palette for the {1}, {2}, ... markers in the art
+//
+// and any other key that names a field sets its label. a file whose lines
+// above its first `---` aren't all comments or known keys is all art
+#[derive(Default)]
+pub struct Config {
+    pub art: Option<String>,
+    pub order: Vec<String>,
+    pub labels: Vec<(String, String)>,
+    pub percent_only: Option<bool>,
+    pub hide_disabled_swap: Option<bool>,
+    pub kernel_short: Option<bool>,
+    pub precision: Option<usize>,
+    pub trim_zeros: Option<bool>,
+    pub date_format: Option<String>,
+    pub separator_char: Option<String>,
+    // SGR parameters, already validated
+    pub color: Option<String>,
+    pub spacing: Option<usize>,
+    pub separator_length: Option<usize>,
+    // SGR parameters, already validated
+    pub label_color: Option<String>,
+    pub cache_ttl: Option<u64>,
+    // SGR parameters, already validated
+    pub colors: Vec<String>,
+}
+
+fn parse_bool(key: &str, value: &str) -> Option<bool> {
+    match value {
+        "true" | "yes" | "on" | "1" => Some(true),
+        "false" | "no" | "off" | "0" => Some(false),
+        _ => {
+            eprintln!("warning: config key '{}' expects true or false", key);
+            None
+        }
+    }
+}
+
+fn unquote(value: &str) -> &str {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+pub fn parse_config(text: &str) -> Config {
+    let mut config = Config::default();
+
+    let lines: Vec<&str> = text.lines().collect();
+    let Some(split) = lines.iter().position(|line| line.trim_end() == "---") else {
+        config.art = clean_art(text.lines());
+        return config;
+    };
+    // art may have a --- line of its own, so it only ends a header when
+    // everything above it reads as one
+    if !is_header(&lines[..split]) {
+        config.art = clean_art(text.lines());
+        return config;
+    }
+
+    for line in &lines[..split] {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            eprintln!("warning: ignoring config line '{}'", line);
+            continue;
+        };
+        let (key, value) = (key.trim(), unquote(value));
+        match key {
+            "order" => config.order = crate::parse_list(value),
+            "percent_only" => config.percent_only = parse_bool(key, value),
+            "hide_disabled_swap" => config.hide_disabled_swap = parse_bool(key, value),
+            "kernel_short" => config.kernel_short = parse_bool(key, value),
+            "trim_zeros" => config.trim_zeros = parse_bool(key, value),
+            "precision" => match value.parse() {
+                Ok(digits) => config.precision = Some(digits),
+                Err(_) => eprintln!("warning: config key '{}' expects a number", key),
+            },
+            "date_format" => config.date_format = Some(value.to_string()),
+            "cache" => match value.parse() {
+                Ok(secs) => config.cache_ttl = Some(secs),
+                Err(_) => eprintln!("warning: config key '{}' expects a number", key),
+            },
+            "separator_char" | "separator" => config.separator_char = Some(value.to_string()),
+            "color" => match crate::color::parse_color(value) {
+                Ok(sgr) => config.color = Some(sgr),
+                Err(err) => eprintln!("warning: config key '{}': {}", key, err),
+            },
+            "spacing" => match value.parse() {
+                Ok(spaces) => config.spacing = Some(spaces),
+                Err(_) => eprintln!("warning: config key '{}' expects a number", key),
+            },
+            "separator_length" => match value.parse() {
+                Ok(len) => config.separator_length = Some(len),
+                Err(_) => eprintln!("warning: config key '{}' expects a number", key),
+            },
+            "label_color" => match crate::color::parse_color(value) {
+                Ok(sgr) => config.label_color = Some(sgr),
+                Err(err) => eprintln!("warning: config key '{}': {}", key, err),
+            },
+            "colors" => {
+                config.colors = crate::parse_list(value)
+                    .iter()
+                    .filter_map(|color| match crate::color::parse_color(color) {
+                        Ok(sgr) => Some(sgr),
+                        Err(err) => {
+                            eprintln!("warning: config key 'colors': {}", err);
+                            None
+                        }
+                    })
+                    .collect()
+            }
+            _ if crate::fields::is_field_key(key) => {
+                config.labels.push((key.to_string(), value.to_string()))
+            }
+            _ => eprintln!("warning: unknown config key '{}'", key),
+        }
+    }
+
+    config.art = clean_art(lines[split + 1..].iter().copied());
+    config
+}
+
+const KEYS: &[&str] = &[
+    "order",
+    "percent_only",
+    "hide_disabled_swap",
+    "kernel_short",
+    "precision",
+    "trim_zeros",
+    "date_format",
+    "color",
+    "spacing",
+    "separator_char",
+    "separator",
+    "separator_length",
+    "label_color",
+    "cache",
+    "colors",
+];
+
+// some non-blank lines, each a comment or a known `key: value`
+fn is_header(lines: &[&str]) -> bool {
+    let mut lines = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .peekable();
+    lines.peek().is_some()
+        && lines.all(|line| {
+            line.starts_with('#')
+                || line.split_once(':').is_some_and(|(key, _)| {
+                    let key = key.trim();
+                    KEYS.contains(&key) || crate::fields::is_field_key(key)
+                })
+        })
+}
+
+// strips the \r of CRLF files and trailing spaces, which would otherwise
+// count towards the art width, plus blank lines around the art; leading
+// spaces are part of the drawing and stay
+fn clean_art<'a>(lines: impl Iterator<Item = &'a str>) -> Option<String> {
+    let lines: Vec<&str> = lines.map(str::trim_end).collect();
+    let start = lines.iter().position(|line| !line.is_empty())?;
+    let end = lines.iter().rposition(|line| !line.is_empty())?;
+    Some(lines[start..=end].join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dashes_in_plain_art_are_art() {
+        let config = parse_config("line one art\n---\nbelow");
+        assert_eq!(config.art.as_deref(), Some("line one art\n---\nbelow"));
+        assert!(config.labels.is_empty());
+    }
+
+    #[test]
+    fn header_above_dashes_is_config() {
+        let config = parse_config("# mine\norder: os, cpu\nos: \"System\"\n---\n art");
+        assert_eq!(config.order, vec!["os", "cpu"]);
+        assert_eq!(
+            config.labels,
+            vec![("os".to_string(), "System".to_string())]
+        );
+        assert_eq!(config.art.as_deref(), Some(" art"));
+    }
+}
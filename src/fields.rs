@@ -0,0 +1,757 @@
+use rfetch::*;
+
+use crate::Options;
+
+// what --list-fields shows about a field key
+pub struct FieldInfo {
+    pub key: &'static str,
+    pub description: &'static str,
+    // opt-in fields are only collected when named in --fields or the config
+    // order (cpu_usage and net_speed because sampling them adds a delay);
+    // the library's OPT_IN_FIELDS lists the same keys
+    pub default_on: bool,
+}
+
+const fn field(key: &'static str, description: &'static str) -> FieldInfo {
+    FieldInfo {
+        key,
+        description,
+        default_on: true,
+    }
+}
+
+const fn opt_in(key: &'static str, description: &'static str) -> FieldInfo {
+    FieldInfo {
+        key,
+        description,
+        default_on: false,
+    }
+}
+
+// every key accepted by --fields/--exclude, in default display order
+pub static FIELDS: [FieldInfo; 52] = [
+    field("title", "user@hostname"),
+    field("uptime", "time since boot"),
+    field("load", "1, 5 and 15 minute load averages"),
+    field("separator", "line under the title"),
+    field("os", "distro name and version"),
+    field("host", "machine vendor, model and version"),
+    field("host_type", "bare metal, virtual machine or container"),
+    field("board", "motherboard vendor and model"),
+    field("bios", "firmware vendor, version and date"),
+    field("wsl", "WSL version, under Windows only"),
+    field("cpu", "model, cores/threads and max frequency"),
+    field("cpu_temp", "cpu package temperature"),
+    opt_in(
+        "cpu_usage",
+        "overall and per core usage, sampled for --cpu-sample ms",
+    ),
+    field("gpu", "model, driver and vram of each gpu"),
+    field("gpu_temp", "temperature of each gpu"),
+    field("packages", "installed packages per package manager"),
+    field("kernel", "kernel name and release"),
+    field("arch", "machine architecture and byte order"),
+    field("init", "init system"),
+    field("disk", "usage of / (or every filesystem with --disks all)"),
+    field("disk_temp", "nvme and sata drive temperatures"),
+    field("memory", "used and total memory"),
+    field(
+        "ram_installed",
+        "installed memory (smbios module sizes, else MemTotal)",
+    ),
+    field(
+        "ram_type",
+        "memory type, speed and slots (needs the smbios table)",
+    ),
+    field("swap", "used and total swap"),
+    field("zram", "compressed swap in ram, when zram is used for swap"),
+    field("battery", "charge and status of each battery"),
+    field("ac", "whether the power adapter is plugged in"),
+    field("brightness", "backlight brightness"),
+    field("terminal", "terminal ($TERM)"),
+    field("font", "terminal font (kitty, alacritty, foot)"),
+    field("shell", "shell and version"),
+    field("editor", "$VISUAL or $EDITOR"),
+    field("wm", "desktop environment or window manager and version"),
+    field("session", "wayland, x11 or tty"),
+    field("theme", "gtk and qt themes"),
+    opt_in("icons", "icon theme"),
+    opt_in("cursor", "cursor theme"),
+    field("users", "logged in users and the current tty"),
+    field("processes", "number of processes"),
+    field("locale", "language and encoding"),
+    field("timezone", "local timezone"),
+    field("date", "current date and time"),
+    field("resolution", "connected displays and refresh rates"),
+    field("audio", "sound server"),
+    field("local_ip", "local address and interface"),
+    field("mac", "hardware address of the local ip interface"),
+    field("gateway", "default route gateway"),
+    field("dns", "dns servers from resolv.conf"),
+    field("wifi", "connected wireless network and signal"),
+    opt_in(
+        "net_speed",
+        "download and upload rate, sampled for --net-sample ms",
+    ),
+    field(
+        "public_ip",
+        "public address (only looked up with --public-ip)",
+    ),
+];
+
+pub fn is_field_key(key: &str) -> bool {
+    FIELDS.iter().any(|field| field.key == key)
+}
+
+// --list-fields: key, description and (opt-in) for the ones that are off
+pub fn list_fields() {
+    let width = FIELDS.iter().map(|f| f.key.len()).max().unwrap_or(0);
+    for field in &FIELDS {
+        let opt_in = if field.default_on { "" } else { " (opt-in)" };
+        println!(
+            "{:<width$}  {}{}",
+            field.key,
+            field.description,
+            opt_in,
+            width = width
+        );
+    }
+}
+
+// one displayed line; fields like gpu can produce several with the same key
+pub struct Field {
+    pub key: &'static str,
+    pub label: String,
+    pub value: String,
+    // the raw numbers behind memory, swap and disk values, for --format kv
+    pub usage: Option<Usage>,
+}
+
+impl Field {
+    fn new(key: &'static str, label: &str, value: String) -> Self {
+        Field {
+            key,
+            label: label.to_string(),
+            value,
+            usage: None,
+        }
+    }
+
+    fn with_usage(mut self, usage: Option<Usage>) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    // label_color is an escape for the label and the visible part of
+    // separator (the ":" of the default ": "), empty for plain
+    pub fn render(&self, label_color: &str, separator: &str) -> String {
+        if self.label.is_empty() {
+            return self.value.clone();
+        }
+        if label_color.is_empty() {
+            return format!("{}{}{}", self.label, separator, self.value);
+        }
+        let mark = separator.trim_end();
+        format!(
+            "{}{}{}\x1b[0m{}{}",
+            label_color,
+            self.label,
+            mark,
+            &separator[mark.len()..],
+            self.value
+        )
+    }
+}
+
+// e.g. AMD Ryzen 7 5800X (8C/16T) @ 3.80GHz, or 2x Intel Xeon Gold 6230
+// (40C/80T) with the cores and threads of all sockets
+fn format_cpu(cpu: &Cpu) -> String {
+    let mut out = match cpu.sockets {
+        Some(sockets) if sockets > 1 => format!("{}x {}", sockets, cpu.model),
+        _ => cpu.model.clone(),
+    };
+    if let (Some(cores), Some(threads)) = (cpu.cores, cpu.threads) {
+        out.push_str(&format!(" ({}C/{}T)", cores, threads));
+    }
+    if let Some(mhz) = cpu.max_freq_mhz {
+        out.push_str(&format!(" @ {:.2}GHz", mhz as f64 / 1000.0));
+    }
+    out
+}
+
+// the model without trademarks, vendor, core count and clock, for --format
+// oneline: "AMD Ryzen 7 5800X 8-Core Processor" is "Ryzen 7 5800X" and
+// "Intel(R) Core(TM) i7-9700K CPU @ 3.60GHz" is "Core i7-9700K"
+fn short_cpu_model(model: &str) -> String {
+    let model = model.split(" @ ").next().unwrap_or(model);
+    let model = model
+        .replace("(R)", "")
+        .replace("(r)", "")
+        .replace("(TM)", "")
+        .replace("(tm)", "");
+    let words: Vec<&str> = model
+        .split_whitespace()
+        .filter(|word| {
+            !matches!(*word, "AMD" | "Intel" | "CPU" | "Processor" | "processor")
+                && !word.ends_with("-Core")
+        })
+        .collect();
+    if words.is_empty() {
+        model.trim().to_string()
+    } else {
+        words.join(" ")
+    }
+}
+
+// user@hostname
+pub fn title(info: &SystemInfo) -> String {
+    format!("{}@{}", info.user, info.hostname)
+}
+
+// accent is the logo color escape (empty without colors), used for bar fills
+pub fn build_fields(
+    info: &SystemInfo,
+    separator: &str,
+    accent: &str,
+    options: &Options,
+) -> Vec<Field> {
+    let glyphs = glyphs(options.ascii_only);
+    // status bar sized values for --format oneline
+    let terse = options.format == crate::Format::Oneline;
+    let or_unknown = |value: &Option<String>| value.as_deref().unwrap_or("unknown").to_string();
+    let bytes = |b: u64| {
+        let precision = options.precision.unwrap_or(crate::DEFAULT_PRECISION);
+        format_bytes_with(b, options.units, precision, options.trim_zeros)
+    };
+    let usage = |usage: Option<Usage>, sep: &str| match usage {
+        Some(u) if options.bars => format!(
+            "{} {}%",
+            render_bar(u.used, u.capacity(), options.bar_width, accent),
+            u.percent()
+        ),
+        Some(u) if options.percent_only => format!("{}%", u.percent()),
+        Some(u) if terse => format_usage_short(u.used, u.total, options.units),
+        Some(u) => format!(
+            "{}{}{} ({}%)",
+            bytes(u.used),
+            sep,
+            bytes(u.total),
+            u.percent()
+        ),
+        None => "unknown".to_string(),
+    };
+
+    let mut fields = vec![
+        Field::new("title", "", title(info)),
+        Field::new(
+            "uptime",
+            "Uptime",
+            match info.uptime {
+                Some(uptime) if terse => format_uptime_short(uptime),
+                Some(uptime) => format_uptime(uptime),
+                None => "unknown".to_string(),
+            },
+        ),
+    ];
+    if let Some([one, five, fifteen]) = info.loadavg {
+        fields.push(Field::new(
+            "load",
+            "Load",
+            format!("{:.2}, {:.2}, {:.2}", one, five, fifteen),
+        ));
+    }
+    fields.extend([
+        Field::new("separator", "", separator.to_string()),
+        Field::new("os", "OS", info.os.name.clone()),
+        Field::new("host", "Host", or_unknown(&info.host)),
+        Field::new("host_type", "Host Type", info.host_type.clone()),
+    ]);
+    if let Some(board) = &info.board {
+        fields.push(Field::new("board", "Motherboard", board.clone()));
+    }
+    if let Some(bios) = &info.bios {
+        fields.push(Field::new("bios", "BIOS", bios.clone()));
+    }
+    if let Some(version) = info.wsl {
+        fields.push(Field::new("wsl", "WSL", version.to_string()));
+    }
+    let cpu = if terse {
+        short_cpu_model(&info.cpu.model)
+    } else {
+        format_cpu(&info.cpu)
+    };
+    fields.push(Field::new("cpu", "CPU", cpu));
+    if let Some(usage) = &info.cpu_usage {
+        let cores: Vec<String> = usage.cores.iter().map(u64::to_string).collect();
+        fields.push(Field::new(
+            "cpu_usage",
+            "CPU Usage",
+            format!("{}% (cores: {})", usage.total, cores.join(",")),
+        ));
+    }
+    if let Some(temp) = info.cpu_temp {
+        fields.push(Field::new(
+            "cpu_temp",
+            "CPU Temp",
+            format_temp(temp, options.fahrenheit, glyphs),
+        ));
+    }
+    if info.gpus.is_empty() {
+        fields.push(Field::new("gpu", "GPU", "unknown".to_string()));
+    }
+    fields.extend(info.gpus.iter().map(|gpu| {
+        // e.g. [amdgpu, 12 GiB], vram sizes are whole numbers more often than not
+        let details: Vec<String> = gpu
+            .driver
+            .iter()
+            .cloned()
+            .chain(
+                gpu.vram
+                    .map(|vram| format_bytes_with(vram, options.units, 2, true)),
+            )
+            .collect();
+        if details.is_empty() {
+            Field::new("gpu", "GPU", gpu.name.clone())
+        } else {
+            Field::new(
+                "gpu",
+                "GPU",
+                format!("{} [{}]", gpu.name, details.join(", ")),
+            )
+        }
+    }));
+    if !info.gpu_temps.is_empty() {
+        let temps: Vec<String> = info
+            .gpu_temps
+            .iter()
+            .map(|&temp| format_temp(temp, options.fahrenheit, glyphs))
+            .collect();
+        fields.push(Field::new("gpu_temp", "GPU Temp", temps.join(", ")));
+    }
+    if !info.packages.is_empty() {
+        let counts: Vec<String> = info
+            .packages
+            .iter()
+            .map(|(manager, n)| format!("{} ({})", n, manager))
+            .collect();
+        fields.push(Field::new("packages", "Packages", counts.join(", ")));
+    }
+    fields.push(Field::new(
+        "kernel",
+        "Kernel",
+        match &info.kernel {
+            Some(kernel) if options.kernel_short || terse => kernel.release.clone(),
+            Some(kernel) => format!("{} {}", kernel.name, kernel.release),
+            None => "unknown".to_string(),
+        },
+    ));
+    if let Some(arch) = &info.arch {
+        // the byte order rfetch was built for, which it has to match to run
+        let endian = if cfg!(target_endian = "little") {
+            "little"
+        } else {
+            "big"
+        };
+        fields.push(Field::new(
+            "arch",
+            "Arch",
+            format!("{} ({} endian)", arch, endian),
+        ));
+    }
+    if let Some(init) = &info.init {
+        fields.push(Field::new("init", "Init", init.clone()));
+    }
+    if info.disks.is_empty() {
+        fields.push(Field::new("disk", "Disk (/)", "unknown".to_string()));
+    }
+    fields.extend(info.disks.iter().map(|d| {
+        Field::new(
+            "disk",
+            &format!("Disk ({})", d.mount),
+            usage(Some(d.usage), " / "),
+        )
+        .with_usage(Some(d.usage))
+    }));
+    // the drive names only matter when there is more than one
+    let disk_temps: Vec<String> = info
+        .disk_temps
+        .iter()
+        .map(|disk| match info.disk_temps.len() {
+            1 => format_temp(disk.celsius, options.fahrenheit, glyphs),
+            _ => format!(
+                "{} ({})",
+                format_temp(disk.celsius, options.fahrenheit, glyphs),
+                disk.device
+            ),
+        })
+        .collect();
+    if !disk_temps.is_empty() {
+        fields.push(Field::new("disk_temp", "Disk Temp", disk_temps.join(", ")));
+    }
+    fields.push(Field::new("memory", "Memory", usage(info.memory, "/")).with_usage(info.memory));
+    if let Some(ram) = &info.ram_installed {
+        let mut value = bytes(ram.bytes);
+        if !ram.from_smbios {
+            value.push_str(" (usable)");
+        }
+        fields.push(Field::new("ram_installed", "Installed RAM", value));
+    }
+    if let Some(ram) = &info.ram {
+        let mut value = ram.kind.clone();
+        if let Some(speed) = ram.speed {
+            value.push_str(&format!(" {}MHz", speed));
+        }
+        value.push_str(&format!(" ({}/{} slots)", ram.populated, ram.slots));
+        fields.push(Field::new("ram_type", "Memory Type", value));
+    }
+    match info.swap {
+        Some(swap) if swap.total == 0 => {
+            if !options.hide_disabled_swap {
+                fields.push(Field::new("swap", "Swap", "disabled".to_string()));
+            }
+        }
+        swap => fields.push(Field::new("swap", "Swap", usage(swap, "/")).with_usage(swap)),
+    }
+    if let Some(zram) = &info.zram {
+        let mut value = format!(
+            "{} / {} compressed",
+            bytes(zram.compressed),
+            bytes(zram.original)
+        );
+        let mut notes = Vec::new();
+        if zram.compressed > 0 {
+            notes.push(format!(
+                "{:.1}x",
+                zram.original as f64 / zram.compressed as f64
+            ));
+        }
+        if let Some(swappiness) = zram.swappiness {
+            notes.push(format!("swappiness {}", swappiness));
+        }
+        if !notes.is_empty() {
+            value.push_str(&format!(" ({})", notes.join(", ")));
+        }
+        fields.push(Field::new("zram", "Swap (zram)", value));
+    }
+    if !info.battery.is_empty() {
+        let readings: Vec<String> = info
+            .battery
+            .iter()
+            .map(|b| format!("{}% ({})", b.capacity, b.status))
+            .collect();
+        fields.push(Field::new("battery", "Battery", readings.join(", ")));
+    }
+    if let Some(online) = info.ac {
+        fields.push(Field::new(
+            "ac",
+            "AC",
+            if online { "connected" } else { "disconnected" }.to_string(),
+        ));
+    }
+    if let Some(brightness) = info.brightness {
+        fields.push(Field::new(
+            "brightness",
+            "Brightness",
+            format!("{}%", brightness),
+        ));
+    }
+    fields.push(Field::new(
+        "terminal",
+        "Terminal",
+        or_unknown(&info.terminal),
+    ));
+    if let Some(font) = &info.font {
+        fields.push(Field::new("font", "Font", font.clone()));
+    }
+    fields.push(Field::new("shell", "Shell", or_unknown(&info.shell)));
+    if let Some(editor) = &info.editor {
+        fields.push(Field::new("editor", "Editor", editor.clone()));
+    }
+    fields.push(match &info.wm {
+        Some(desktop) => Field::new(
+            "wm",
+            if desktop.is_de { "DE" } else { "WM" },
+            match &desktop.version {
+                Some(version) => format!("{} {}", desktop.name, version),
+                None => desktop.name.clone(),
+            },
+        ),
+        None => Field::new("wm", "WM", "unknown".to_string()),
+    });
+    if let Some(session) = &info.session {
+        fields.push(Field::new("session", "Session", session.clone()));
+    }
+    if let Some(theme) = &info.theme {
+        fields.push(Field::new("theme", "Theme", theme.clone()));
+    }
+    if let Some(icons) = &info.icons {
+        fields.push(Field::new("icons", "Icons", icons.clone()));
+    }
+    if let Some(cursor) = &info.cursor {
+        fields.push(Field::new("cursor", "Cursor", cursor.clone()));
+    }
+    fields.push(Field::new(
+        "users",
+        "Users",
+        match &info.tty {
+            Some(tty) => format!("{} ({})", info.users, tty),
+            None => info.users.to_string(),
+        },
+    ));
+    if let Some(processes) = info.processes {
+        fields.push(Field::new("processes", "Processes", processes.to_string()));
+    }
+    fields.extend([
+        Field::new("locale", "Locale", or_unknown(&info.locale)),
+        Field::new("timezone", "Timezone", or_unknown(&info.timezone)),
+    ]);
+    if let Some(date) = info
+        .time
+        .and_then(|time| format_local_time(time, &options.date_format))
+    {
+        fields.push(Field::new("date", "Date", date));
+    }
+    if !info.displays.is_empty() {
+        let modes: Vec<String> = info
+            .displays
+            .iter()
+            .map(|d| match d.refresh {
+                Some(hz) => format!("{}@{}Hz", d.mode, hz),
+                None => d.mode.clone(),
+            })
+            .collect();
+        fields.push(Field::new(
+            "resolution",
+            "Display",
+            match modes.len() {
+                1 => modes.join(""),
+                n => format!("{} monitors ({})", n, modes.join(", ")),
+            },
+        ));
+    }
+    if let Some(audio) = &info.audio {
+        fields.push(Field::new("audio", "Audio", audio.clone()));
+    }
+    fields.push(Field::new(
+        "local_ip",
+        "Local IP",
+        match &info.local_ip {
+            // WSL2 runs behind a NAT by default, so this is the VM's address
+            // rather than the Windows one, which can't be read from inside
+            // (mirrored networking shares the host's, but still gets the note)
+            Some(ip) if info.wsl == Some(2) => format!("{} ({}, WSL2 VM)", ip.addr, ip.iface),
+            Some(ip) => format!("{} ({})", ip.addr, ip.iface),
+            None => "unknown".to_string(),
+        },
+    ));
+    if let Some(mac) = &info.mac {
+        let mac = if options.mask_mac {
+            mask_mac(mac)
+        } else {
+            mac.clone()
+        };
+        fields.push(Field::new("mac", "MAC", mac));
+    }
+    if let Some(gateway) = info.gateway {
+        fields.push(Field::new("gateway", "Gateway", gateway.to_string()));
+    }
+    if let Some(dns) = &info.dns {
+        let servers = dns.servers.join(", ");
+        let value = if !dns.stub {
+            servers
+        } else if dns.servers == [RESOLVED_STUB] {
+            format!("{} (systemd-resolved stub)", servers)
+        } else {
+            format!("{} (via systemd-resolved)", servers)
+        };
+        fields.push(Field::new("dns", "DNS", value));
+    }
+    if let Some(wifi) = &info.wifi {
+        let name = wifi.ssid.as_deref().unwrap_or(&wifi.iface);
+        fields.push(Field::new(
+            "wifi",
+            "Wi-Fi",
+            match wifi.signal {
+                Some(signal) => format!("{} ({}%)", name, signal),
+                None => name.to_string(),
+            },
+        ));
+    }
+    if let Some(speed) = &info.net_speed {
+        fields.push(Field::new(
+            "net_speed",
+            "Net",
+            format!(
+                "{} {}/s {} {}/s",
+                glyphs.down,
+                bytes(speed.rx),
+                glyphs.up,
+                bytes(speed.tx)
+            ),
+        ));
+    }
+    if options.collect.public_ip {
+        fields.push(Field::new(
+            "public_ip",
+            "Public IP",
+            match info.public_ip {
+                Some(ip) => ip.to_string(),
+                None => "unknown".to_string(),
+            },
+        ));
+    }
+    fields
+}
+
+// keeps the vendor part (the first three octets) for --mask-mac
+fn mask_mac(mac: &str) -> String {
+    mac.split(':')
+        .enumerate()
+        .map(|(i, octet)| if i < 3 { octet } else { "xx" })
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+// what --format oneline shows without --fields
+pub static ONELINE_KEYS: [&str; 5] = ["os", "kernel", "cpu", "memory", "uptime"];
+
+// --format oneline: just the values, joined by pipes (the separator line
+// has no value worth showing)
+pub fn oneline(fields: &[Field]) -> String {
+    let values: Vec<&str> = fields
+        .iter()
+        .filter(|field| field.key != "separator")
+        .map(|field| field.value.as_str())
+        .collect();
+    values.join(" | ")
+}
+
+// --format kv: key='value' lines that are safe to eval, keys repeating (gpu,
+// disk) get _2, _3, ...; usage fields add their raw _used and _total bytes
+pub fn kv_lines(fields: &[Field]) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut seen: Vec<&str> = Vec::new();
+    for field in fields.iter().filter(|f| f.key != "separator") {
+        seen.push(field.key);
+        let name = match seen.iter().filter(|k| **k == field.key).count() {
+            1 => field.key.to_string(),
+            n => format!("{}_{}", field.key, n),
+        };
+        lines.push(format!("{}='{}'", name, field.value.replace('\'', "'\\''")));
+        if let Some(usage) = field.usage {
+            lines.push(format!("{}_used={}", name, usage.used));
+            lines.push(format!("{}_total={}", name, usage.total));
+        }
+    }
+    lines
+}
+
+// unknown keys are reported but otherwise ignored
+pub fn warn_unknown_keys(keys: &[String]) {
+    for key in keys {
+        if !is_field_key(key) {
+            eprintln!("warning: unknown field '{}'", key);
+        }
+    }
+}
+
+// the keys select_fields would keep, so collection can skip the rest; the
+// separator is as long as the title, so it needs that collected too
+pub fn active_keys(only: Option<&[String]>, exclude: &[String], order: &[String]) -> Vec<String> {
+    let named = |key: &str| only.unwrap_or(order).iter().any(|k| k == key);
+    let mut keys: Vec<String> = FIELDS
+        .iter()
+        .filter(|field| match only {
+            Some(only) => only.iter().any(|k| k == field.key),
+            None => field.default_on || named(field.key),
+        })
+        .filter(|field| !exclude.iter().any(|k| k == field.key))
+        .map(|field| field.key.to_string())
+        .collect();
+    if keys.iter().any(|k| k == "separator") && !keys.iter().any(|k| k == "title") {
+        keys.push("title".to_string());
+    }
+    keys
+}
+
+// pulls fields out of slots in the order of keys
+fn take_in_order(slots: &mut [Option<Field>], keys: &[String]) -> Vec<Field> {
+    let mut ordered = Vec::new();
+    for key in keys {
+        for slot in slots.iter_mut() {
+            if slot.as_ref().is_some_and(|f| f.key == key) {
+                ordered.extend(slot.take());
+            }
+        }
+    }
+    ordered
+}
+
+// --fields picks (and orders) what's shown, --exclude then drops from that
+pub fn select_fields(
+    fields: Vec<Field>,
+    only: Option<&[String]>,
+    exclude: &[String],
+) -> Vec<Field> {
+    let selected = match only {
+        Some(keys) => {
+            let mut slots: Vec<Option<Field>> = fields.into_iter().map(Some).collect();
+            take_in_order(&mut slots, keys)
+        }
+        None => fields,
+    };
+
+    selected
+        .into_iter()
+        .filter(|f| !exclude.iter().any(|key| key == f.key))
+        .collect()
+}
+
+// fields named in the config order come first, the rest keep theirs
+pub fn order_fields(fields: Vec<Field>, order: &[String]) -> Vec<Field> {
+    if order.is_empty() {
+        return fields;
+    }
+    let mut slots: Vec<Option<Field>> = fields.into_iter().map(Some).collect();
+    let mut ordered = take_in_order(&mut slots, order);
+    ordered.extend(slots.into_iter().flatten());
+    ordered
+}
+
+pub fn relabel_fields(fields: &mut [Field], labels: &[(String, String)]) {
+    for field in fields.iter_mut() {
+        if let Some((_, label)) = labels.iter().find(|(key, _)| key == field.key) {
+            field.label = label.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_cpu_model_drops_the_marketing() {
+        assert_eq!(
+            short_cpu_model("AMD Ryzen 7 5800X 8-Core Processor"),
+            "Ryzen 7 5800X"
+        );
+        assert_eq!(
+            short_cpu_model("Intel(R) Core(TM) i7-9700K CPU @ 3.60GHz"),
+            "Core i7-9700K"
+        );
+        assert_eq!(short_cpu_model("Intel(R) Xeon(R) Processor"), "Xeon");
+        assert_eq!(short_cpu_model("Apple M2"), "Apple M2");
+        assert_eq!(short_cpu_model("Intel Processor"), "Intel Processor");
+    }
+
+    #[test]
+    fn opt_in_fields_match_the_library() {
+        let opt_in: Vec<&str> = FIELDS
+            .iter()
+            .filter(|field| !field.default_on)
+            .map(|field| field.key)
+            .collect();
+        assert_eq!(opt_in, OPT_IN_FIELDS);
+    }
+}
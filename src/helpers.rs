@@ -1,41 +1,648 @@
 use std::env;
 use std::fs;
+use std::time::Duration;
 
-pub fn read_meminfo_fields(fields: &[&str]) -> Vec<u64> {
-    let mut results = vec![0; fields.len()];
-    for line in fs::read_to_string("/proc/meminfo").unwrap().lines() {
+// missing or unparsable fields come back as None instead of panicking
+pub fn read_meminfo_fields(fields: &[&str]) -> Vec<Option<u64>> {
+    read_meminfo_fields_from("/proc/meminfo", fields)
+}
+
+fn read_meminfo_fields_from(path: &str, fields: &[&str]) -> Vec<Option<u64>> {
+    match fs::read_to_string(path) {
+        Ok(meminfo) => parse_meminfo(&meminfo, fields),
+        Err(_) => vec![None; fields.len()],
+    }
+}
+
+pub fn parse_meminfo(meminfo: &str, fields: &[&str]) -> Vec<Option<u64>> {
+    let mut results = vec![None; fields.len()];
+    for line in meminfo.lines() {
         for (i, &field) in fields.iter().enumerate() {
             if line.starts_with(field) {
-                results[i] = line.split_whitespace().nth(1).unwrap().parse().unwrap();
+                results[i] = line.split_whitespace().nth(1).and_then(|v| v.parse().ok());
             }
         }
     }
     results
 }
 
-pub fn format_bytes(b: u64) -> String {
-    let b = b as f64;
-    if b >= (1u64 << 40) as f64 {
-        format!("{:.2} TiB", b / (1u64 << 40) as f64)
-    } else if b >= (1u64 << 30) as f64 {
-        format!("{:.2} GiB", b / (1u64 << 30) as f64)
-    } else if b >= (1u64 << 20) as f64 {
-        format!("{:.2} MiB", b / (1u64 << 20) as f64)
-    } else if b >= (1u64 << 10) as f64 {
-        format!("{:.2} KiB", b / (1u64 << 10) as f64)
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum Units {
+    // powers of 1024: KiB, MiB, GiB, TiB
+    #[default]
+    Binary,
+    // powers of 1000: KB, MB, GB, TB
+    Decimal,
+}
+
+pub fn format_bytes(b: u64, units: Units) -> String {
+    format_bytes_with(b, units, 2, false)
+}
+
+// the largest unit b is at least one of, with its size in bytes; None
+// below 1 KiB (or KB)
+fn byte_unit(b: u64, units: Units) -> Option<(f64, &'static str)> {
+    let (base, labels) = match units {
+        Units::Binary => (1024f64, ["KiB", "MiB", "GiB", "TiB"]),
+        Units::Decimal => (1000f64, ["KB", "MB", "GB", "TB"]),
+    };
+    labels
+        .iter()
+        .enumerate()
+        .rev()
+        .map(|(power, &label)| (base.powi(power as i32 + 1), label))
+        .find(|&(unit, _)| b as f64 >= unit)
+}
+
+fn format_number(value: f64, precision: usize, trim_zeros: bool) -> String {
+    let value = format!("{:.*}", precision, value);
+    match value.split_once('.') {
+        Some((whole, decimals)) if trim_zeros && decimals.bytes().all(|d| d == b'0') => {
+            whole.to_string()
+        }
+        _ => value,
+    }
+}
+
+// precision decimals, which trim_zeros drops when they're all zero
+// ("15 GiB" rather than "15.00 GiB"); plain bytes never have any
+pub fn format_bytes_with(b: u64, units: Units, precision: usize, trim_zeros: bool) -> String {
+    match byte_unit(b, units) {
+        Some((unit, label)) => format!(
+            "{} {}",
+            format_number(b as f64 / unit, precision, trim_zeros),
+            label
+        ),
+        None => format!("{} B", b),
+    }
+}
+
+// used/total in the unit of total with at most one decimal, e.g.
+// "12.3/31.2 GiB", for --format oneline
+pub fn format_usage_short(used: u64, total: u64, units: Units) -> String {
+    match byte_unit(total, units) {
+        Some((unit, label)) => format!(
+            "{}/{} {}",
+            format_number(used as f64 / unit, 1, true),
+            format_number(total as f64 / unit, 1, true),
+            label
+        ),
+        None => format!("{}/{} B", used, total),
+    }
+}
+
+pub fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let parts = [
+        (secs / 86400, "day", "days"),
+        ((secs % 86400) / 3600, "hour", "hours"),
+        ((secs % 3600) / 60, "min", "mins"),
+    ];
+
+    let shown: Vec<String> = parts
+        .iter()
+        .filter(|(n, _, _)| *n > 0)
+        .map(|&(n, one, many)| format!("{} {}", n, if n == 1 { one } else { many }))
+        .collect();
+
+    if shown.is_empty() {
+        return "0 mins".to_string();
+    }
+    shown.join(", ")
+}
+
+// just the largest part, e.g. "up 3d", "up 5h" or "up 42m"
+pub fn format_uptime_short(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    match (secs / 86400, secs / 3600, secs / 60) {
+        (0, 0, mins) => format!("up {}m", mins),
+        (0, hours, _) => format!("up {}h", hours),
+        (days, _, _) => format!("up {}d", days),
+    }
+}
+
+// strftime in the local timezone, None for an empty or overlong result
+pub fn format_local_time(unix_secs: u64, format: &str) -> Option<String> {
+    let format = std::ffi::CString::new(format).ok()?;
+    let time = unix_secs as libc::time_t;
+    let mut buf = [0u8; 256];
+    let len = unsafe {
+        let mut tm: libc::tm = std::mem::zeroed();
+        if libc::localtime_r(&time, &mut tm).is_null() {
+            return None;
+        }
+        libc::strftime(
+            buf.as_mut_ptr() as *mut libc::c_char,
+            buf.len(),
+            format.as_ptr(),
+            &tm,
+        )
+    };
+    (len > 0).then(|| String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+// the non-ascii characters rfetch draws itself, all chosen here so
+// --ascii-only can swap them for its plain versions
+pub struct Glyphs {
+    pub degree: &'static str,
+    // net_speed's download and upload rates
+    pub down: &'static str,
+    pub up: &'static str,
+    // ends lines truncate_to_width had to cut
+    pub ellipsis: &'static str,
+}
+
+static UNICODE_GLYPHS: Glyphs = Glyphs {
+    degree: "°",
+    down: "↓",
+    up: "↑",
+    ellipsis: "…",
+};
+
+static ASCII_GLYPHS: Glyphs = Glyphs {
+    degree: " deg",
+    down: "rx",
+    up: "tx",
+    ellipsis: "...",
+};
+
+pub fn glyphs(ascii_only: bool) -> &'static Glyphs {
+    if ascii_only {
+        &ASCII_GLYPHS
+    } else {
+        &UNICODE_GLYPHS
+    }
+}
+
+pub fn format_temp(celsius: f64, fahrenheit: bool, glyphs: &Glyphs) -> String {
+    if fahrenheit {
+        format!("{:.1}{}F", celsius * 9.0 / 5.0 + 32.0, glyphs.degree)
+    } else {
+        format!("{:.1}{}C", celsius, glyphs.degree)
+    }
+}
+
+// e.g. [#####-----], the filled part uses color unless it's empty; a total of
+// 0 renders an empty bar
+pub fn render_bar(used: u64, total: u64, width: usize, color: &str) -> String {
+    let filled = if total == 0 {
+        0
     } else {
-        format!("{} B", b as u64)
+        ((used as u128 * width as u128 / total as u128) as usize).min(width)
+    };
+    let fill = "#".repeat(filled);
+    let empty = "-".repeat(width - filled);
+    if color.is_empty() || filled == 0 {
+        return format!("[{}{}]", fill, empty);
     }
+    format!("[{}{}\x1b[0m{}]", color, fill, empty)
 }
 
-pub fn read_file_trim(path: &str) -> String {
-    fs::read_to_string(path).unwrap().trim().to_string()
+// only the end is trimmed, leading spaces can be part of ascii art
+pub fn read_file_trim(path: &str) -> std::io::Result<String> {
+    Ok(fs::read_to_string(path)?.trim_end().to_string())
 }
 
-pub fn make_separator(len: usize, color: &str) -> String {
-    format!("{}{}{}", color, "-".repeat(len), "\x1b[0m")
+// all of stdin, trimmed like read_file_trim; None when stdin is a terminal,
+// where reading would wait for input nobody is going to type
+pub fn read_stdin_trim() -> Option<std::io::Result<String>> {
+    if unsafe { libc::isatty(libc::STDIN_FILENO) } == 1 {
+        return None;
+    }
+    let mut text = String::new();
+    Some(
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)
+            .map(|_| text.trim_end().to_string()),
+    )
+}
+
+// pattern (usually one char) repeated to len columns, never overshooting with
+// wide chars; an empty color means plain output, so no reset code either
+pub fn make_separator(len: usize, pattern: &str, color: &str) -> String {
+    let mut line = String::new();
+    let mut width = 0;
+    for c in pattern.chars().filter(|&c| char_width(c) > 0).cycle() {
+        if width + char_width(c) > len {
+            break;
+        }
+        width += char_width(c);
+        line.push(c);
+    }
+    if color.is_empty() {
+        return line;
+    }
+    format!("{}{}{}", color, line, "\x1b[0m")
+}
+
+// https://no-color.org: any non-empty NO_COLOR disables escapes
+pub fn no_color_env() -> bool {
+    env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty())
+}
+
+// background colored blocks for the given 256-color indices, 8 to a row
+// (the default 0-15 makes the usual normal and bright rows)
+pub fn color_blocks(indices: &[u8]) -> Vec<String> {
+    // 0-15 as the basic codes, which 16 color terminals understand too
+    let code = |i: u8| match i {
+        0..=7 => format!("{}", 40 + i),
+        8..=15 => format!("{}", 100 + i - 8),
+        _ => format!("48;5;{}", i),
+    };
+    indices
+        .chunks(8)
+        .map(|row| {
+            let blocks: String = row
+                .iter()
+                .map(|&i| format!("\x1b[{}m   ", code(i)))
+                .collect();
+            format!("{}\x1b[0m", blocks)
+        })
+        .collect()
+}
+
+// columns of the terminal attached to stdout, None when piped or unknown
+pub fn terminal_width() -> Option<usize> {
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0 && size.ws_col > 0 {
+            Some(size.ws_col as usize)
+        } else {
+            None
+        }
+    }
 }
 
 pub fn evod(var: &str, default: &str) -> String {
     env::var(var).unwrap_or(default.to_string())
 }
+
+pub fn count_dirs(path: &str) -> Option<usize> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+            .count(),
+    )
+}
+
+// removes CSI escape sequences like \x1b[1;36m, and string sequences like
+// kitty graphics (ESC _) or sixel (ESC P) data, so only visible text remains
+pub fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            match chars.next() {
+                // parameters and intermediates run until a final byte in @..~
+                Some('[') => {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                // string sequences run until ST (ESC \) or BEL
+                Some('_' | 'P' | ']') => {
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        // combining marks, zero width spaces/joiners and variation selectors
+        0x0300..=0x036F
+        | 0x1AB0..=0x1AFF
+        | 0x1DC0..=0x1DFF
+        | 0x200B..=0x200F
+        | 0x20D0..=0x20FF
+        | 0xFE00..=0xFE0F
+        | 0xFE20..=0xFE2F => 0,
+        _ if c.is_control() => 0,
+        // east asian wide/fullwidth and emoji blocks; of the older symbol
+        // blocks (2300s to 2b00s) only the emoji-presentation ones, so
+        // arrows, stars and dingbats like ❯ stay one column
+        0x1100..=0x115F
+        | 0x231A..=0x231B
+        | 0x2329..=0x232A
+        | 0x23E9..=0x23EC
+        | 0x23F0
+        | 0x23F3
+        | 0x25FD..=0x25FE
+        | 0x2614..=0x2615
+        | 0x2648..=0x2653
+        | 0x267F
+        | 0x2693
+        | 0x26A1
+        | 0x26AA..=0x26AB
+        | 0x26BD..=0x26BE
+        | 0x26C4..=0x26C5
+        | 0x26CE
+        | 0x26D4
+        | 0x26EA
+        | 0x26F2..=0x26F3
+        | 0x26F5
+        | 0x26FA
+        | 0x26FD
+        | 0x2705
+        | 0x270A..=0x270B
+        | 0x2728
+        | 0x274C
+        | 0x274E
+        | 0x2753..=0x2755
+        | 0x2757
+        | 0x2795..=0x2797
+        | 0x27B0
+        | 0x27BF
+        | 0x2B1B..=0x2B1C
+        | 0x2B50
+        | 0x2B55
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x1F004
+        | 0x1F0CF
+        | 0x1F18E
+        | 0x1F191..=0x1F19A
+        | 0x1F200..=0x1F251
+        | 0x1F300..=0x1F64F
+        | 0x1F680..=0x1F6FF
+        | 0x1F7E0..=0x1F7EB
+        | 0x1F900..=0x1F9FF
+        | 0x1FA70..=0x1FAFF
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+// number of terminal columns a string occupies once escapes are ignored
+pub fn display_width(s: &str) -> usize {
+    strip_ansi(s).chars().map(char_width).sum()
+}
+
+// the ascii stand-in for a non-ascii char in user-supplied art, separators
+// and labels, for --ascii-only (rfetch's own glyphs come from Glyphs); the
+// usual box drawing characters get a look-alike, anything else a '?'. none
+// is wider than what it replaces, so the art still lines up
+fn ascii_fallback(c: char) -> &'static str {
+    match c {
+        '…' => ".",
+        '❯' | '›' | '»' | '→' => ">",
+        '‹' | '«' | '←' => "<",
+        '─' | '━' | '═' | '–' | '—' => "-",
+        '│' | '┃' | '║' => "|",
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' | '┬' | '┴' | '┼' | '╭' | '╮' | '╯' | '╰' => {
+            "+"
+        }
+        '█' | '▓' | '▒' | '░' | '■' => "#",
+        '•' | '·' | '●' => "*",
+        _ => "?",
+    }
+}
+
+// s with every non-ascii char downgraded; escape sequences are ascii already
+pub fn to_ascii(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        if c.is_ascii() {
+            out.push(c);
+        } else {
+            out.push_str(ascii_fallback(c));
+        }
+    }
+    out
+}
+
+// cuts s down to max columns, ending in ellipsis when anything was dropped;
+// escapes are kept (and closed with a reset) so colors still apply
+pub fn truncate_to_width(s: &str, max: usize, ellipsis: &str) -> String {
+    if display_width(s) <= max {
+        return s.to_string();
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut width = 0;
+    let mut escaped = false;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            escaped = true;
+            out.push(c);
+            if let Some(next) = chars.next() {
+                out.push(next);
+                if next == '[' {
+                    for c in chars.by_ref() {
+                        out.push(c);
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            }
+            continue;
+        }
+        let w = char_width(c);
+        if width + w + display_width(ellipsis) > max {
+            break;
+        }
+        width += w;
+        out.push(c);
+    }
+    if max >= display_width(ellipsis) {
+        out.push_str(ellipsis);
+    }
+    if escaped {
+        out.push_str("\x1b[0m");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_meminfo_reads_as_none() {
+        let values = read_meminfo_fields_from("/nonexistent/meminfo", &["MemTotal:", "SwapFree:"]);
+        assert_eq!(values, vec![None, None]);
+    }
+
+    #[test]
+    fn malformed_meminfo_lines_are_skipped() {
+        let meminfo = "MemTotal:       16318480 kB\nMemFree:\nMemAvailable: lots kB\ngarbage\nSwapTotal: 2097148 kB\n";
+        let values = parse_meminfo(
+            meminfo,
+            &[
+                "MemTotal:",
+                "MemFree:",
+                "MemAvailable:",
+                "SwapTotal:",
+                "SwapFree:",
+            ],
+        );
+        assert_eq!(
+            values,
+            vec![Some(16318480), None, None, Some(2097148), None]
+        );
+    }
+
+    const GIB: u64 = 1024 * 1024 * 1024;
+
+    #[test]
+    fn bytes_keep_their_decimals_unless_trimmed() {
+        assert_eq!(
+            format_bytes_with(15 * GIB, Units::Binary, 2, false),
+            "15.00 GiB"
+        );
+        assert_eq!(
+            format_bytes_with(15 * GIB, Units::Binary, 2, true),
+            "15 GiB"
+        );
+        assert_eq!(
+            format_bytes_with(15 * GIB, Units::Binary, 0, false),
+            "15 GiB"
+        );
+        // only an all zero fraction is trimmed
+        let value = 15 * GIB + GIB / 2;
+        assert_eq!(
+            format_bytes_with(value, Units::Binary, 2, true),
+            "15.50 GiB"
+        );
+        assert_eq!(
+            format_bytes_with(value, Units::Binary, 3, false),
+            "15.500 GiB"
+        );
+    }
+
+    #[test]
+    fn decimal_bytes_use_powers_of_1000() {
+        assert_eq!(
+            format_bytes_with(1_500_000_000, Units::Decimal, 2, false),
+            "1.50 GB"
+        );
+        assert_eq!(
+            format_bytes_with(2_000_000, Units::Decimal, 1, true),
+            "2 MB"
+        );
+        assert_eq!(format_bytes(1000, Units::Decimal), "1.00 KB");
+        assert_eq!(format_bytes(1000, Units::Binary), "1000 B");
+    }
+
+    #[test]
+    fn bytes_below_a_kib_have_no_decimals() {
+        assert_eq!(format_bytes_with(0, Units::Binary, 2, false), "0 B");
+        assert_eq!(format_bytes_with(1023, Units::Binary, 3, false), "1023 B");
+        assert_eq!(format_bytes_with(1024, Units::Binary, 0, false), "1 KiB");
+    }
+
+    #[test]
+    fn short_usage_shares_the_unit_of_the_total() {
+        assert_eq!(
+            format_usage_short(12 * GIB + GIB * 3 / 10, 31 * GIB + GIB / 5, Units::Binary),
+            "12.3/31.2 GiB"
+        );
+        assert_eq!(
+            format_usage_short(512 * 1024 * 1024, 16 * GIB, Units::Binary),
+            "0.5/16 GiB"
+        );
+        assert_eq!(format_usage_short(500, 900, Units::Binary), "500/900 B");
+    }
+
+    #[test]
+    fn short_uptime_is_the_largest_part() {
+        assert_eq!(
+            format_uptime_short(Duration::from_secs(3 * 86400 + 7200)),
+            "up 3d"
+        );
+        assert_eq!(
+            format_uptime_short(Duration::from_secs(5 * 3600 + 59 * 60)),
+            "up 5h"
+        );
+        assert_eq!(format_uptime_short(Duration::from_secs(42 * 60)), "up 42m");
+        assert_eq!(format_uptime_short(Duration::from_secs(10)), "up 0m");
+    }
+
+    #[test]
+    fn emoji_are_two_columns_but_symbols_one() {
+        assert_eq!(display_width("⚡✅❌⭐"), 8);
+        assert_eq!(display_width("🚀🟢🫠"), 6);
+        assert_eq!(display_width("❯★→─"), 4);
+        assert_eq!(display_width("日本"), 4);
+        assert_eq!(display_width("e\u{301}\x1b[1m"), 1);
+    }
+
+    #[test]
+    fn temperatures_use_the_chosen_degree_sign() {
+        assert_eq!(format_temp(54.0, false, glyphs(false)), "54.0°C");
+        assert_eq!(format_temp(54.0, false, glyphs(true)), "54.0 degC");
+        assert_eq!(format_temp(100.0, true, glyphs(true)), "212.0 degF");
+    }
+
+    #[test]
+    fn truncation_leaves_room_for_the_ellipsis() {
+        assert_eq!(
+            truncate_to_width("abcdefgh", 6, glyphs(false).ellipsis),
+            "abcde…"
+        );
+        assert_eq!(
+            truncate_to_width("abcdefgh", 6, glyphs(true).ellipsis),
+            "abc..."
+        );
+        assert_eq!(
+            truncate_to_width("abcdef", 6, glyphs(true).ellipsis),
+            "abcdef"
+        );
+        assert_eq!(
+            truncate_to_width("\x1b[1mabcdefgh", 4, glyphs(true).ellipsis),
+            "\x1b[1ma...\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn uptime_under_a_minute_is_zero_mins() {
+        assert_eq!(format_uptime(Duration::from_secs(0)), "0 mins");
+        assert_eq!(format_uptime(Duration::from_secs(59)), "0 mins");
+    }
+
+    #[test]
+    fn uptime_units_are_singular_only_for_one() {
+        assert_eq!(format_uptime(Duration::from_secs(60)), "1 min");
+        assert_eq!(format_uptime(Duration::from_secs(86400)), "1 day");
+        assert_eq!(format_uptime(Duration::from_secs(2 * 86400)), "2 days");
+        assert_eq!(
+            format_uptime(Duration::from_secs(86400 + 3600 + 60)),
+            "1 day, 1 hour, 1 min"
+        );
+        assert_eq!(
+            format_uptime(Duration::from_secs(10 * 86400 + 3 * 3600 + 12 * 60)),
+            "10 days, 3 hours, 12 mins"
+        );
+    }
+
+    #[test]
+    fn uptime_leaves_out_zero_parts() {
+        assert_eq!(
+            format_uptime(Duration::from_secs(2 * 86400 + 5 * 60)),
+            "2 days, 5 mins"
+        );
+        assert_eq!(format_uptime(Duration::from_secs(3 * 86400)), "3 days");
+        assert_eq!(format_uptime(Duration::from_secs(42 * 60 + 30)), "42 mins");
+    }
+}
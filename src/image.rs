@@ -0,0 +1,164 @@
+// --image: an actual picture in the logo column, via the kitty graphics
+// protocol (png files) or sixel (files that are already sixel encoded)
+use std::{env, fs};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Kitty,
+    Sixel,
+}
+
+// terminals advertise neither reliably, so this goes by what they export
+pub fn detect_protocol() -> Option<Protocol> {
+    let term = env::var("TERM").unwrap_or_default();
+    let program = env::var("TERM_PROGRAM").unwrap_or_default();
+    if env::var_os("KITTY_WINDOW_ID").is_some()
+        || term == "xterm-kitty"
+        || term == "xterm-ghostty"
+        || program == "WezTerm"
+    {
+        return Some(Protocol::Kitty);
+    }
+    if term.contains("sixel") || term.starts_with("foot") || term.starts_with("mlterm") {
+        return Some(Protocol::Sixel);
+    }
+    None
+}
+
+// pixel size of one character cell, assuming the common 1:2 when the
+// terminal doesn't report its pixel dimensions
+fn cell_size() -> (usize, usize) {
+    unsafe {
+        let mut size: libc::winsize = std::mem::zeroed();
+        if libc::ioctl(libc::STDOUT_FILENO, libc::TIOCGWINSZ, &mut size) == 0
+            && size.ws_xpixel > 0
+            && size.ws_ypixel > 0
+            && size.ws_col > 0
+            && size.ws_row > 0
+        {
+            return (
+                size.ws_xpixel as usize / size.ws_col as usize,
+                size.ws_ypixel as usize / size.ws_row as usize,
+            );
+        }
+    }
+    (8, 16)
+}
+
+// width and height from the IHDR chunk, which always comes first
+fn png_size(data: &[u8]) -> Option<(usize, usize)> {
+    if !data.starts_with(b"\x89PNG\r\n\x1a\n") || data.len() < 24 || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let read = |i: usize| u32::from_be_bytes([data[i], data[i + 1], data[i + 2], data[i + 3]]);
+    Some((read(16) as usize, read(20) as usize))
+}
+
+// pixel size from the raster attributes ("Pan;Pad;Ph;Pv) after the DCS intro
+fn sixel_size(data: &str) -> Option<(usize, usize)> {
+    let body = &data[data.find('q')? + 1..];
+    let attrs = body.strip_prefix('"')?;
+    let end = attrs.find(|c: char| !c.is_ascii_digit() && c != ';')?;
+    let values: Vec<usize> = attrs[..end]
+        .split(';')
+        .filter_map(|v| v.parse().ok())
+        .collect();
+    match values[..] {
+        [_, _, w, h] => Some((w, h)),
+        _ => None,
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(TABLE[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+// the png is sent inline (so it works over ssh) in 4096 byte chunks, placed
+// at c x r cells without moving the cursor (C=1) and with replies off (q=2)
+fn kitty_sequence(data: &[u8], cols: usize, rows: usize) -> String {
+    let encoded = base64(data);
+    let chunks: Vec<&str> = encoded
+        .as_bytes()
+        .chunks(4096)
+        .map(|c| std::str::from_utf8(c).unwrap_or_default())
+        .collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = if i + 1 < chunks.len() { 1 } else { 0 };
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=100,q=2,C=1,c={},r={},m={};{}\x1b\\",
+                cols, rows, more, chunk
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={};{}\x1b\\", more, chunk));
+        }
+    }
+    out
+}
+
+// art lines for print_stuff: blank cells the size of the image, with the
+// image itself drawn from the first one. kitty scales the image to rows
+// lines (usually the number of info lines), sixel is shown at its own size
+pub fn image_lines(
+    path: &str,
+    protocol: Protocol,
+    rows: usize,
+    spacing: usize,
+) -> Result<Vec<String>, String> {
+    let data = fs::read(path).map_err(|err| format!("can't read image '{}': {}", path, err))?;
+    let rows = rows.max(1);
+    let (cell_w, cell_h) = cell_size();
+
+    let (sequence, (width, height)) = match protocol {
+        Protocol::Kitty => {
+            let size = png_size(&data)
+                .ok_or_else(|| format!("'{}' is not a png (kitty images must be png)", path))?;
+            (String::new(), size)
+        }
+        Protocol::Sixel => {
+            let text = String::from_utf8_lossy(&data).into_owned();
+            let size = sixel_size(&text).ok_or_else(|| {
+                format!("'{}' is not a sixel file (convert it with img2sixel)", path)
+            })?;
+            (text, size)
+        }
+    };
+    if width == 0 || height == 0 {
+        return Err(format!("'{}' has no pixels", path));
+    }
+
+    let (cols, rows) = match protocol {
+        // keep the aspect ratio: rows of cell_h pixels, as many columns as that needs
+        Protocol::Kitty => ((rows * cell_h * width / (height * cell_w)).max(1), rows),
+        Protocol::Sixel => (width.div_ceil(cell_w), height.div_ceil(cell_h)),
+    };
+    let sequence = match protocol {
+        Protocol::Kitty => kitty_sequence(&data, cols, rows),
+        // sixel moves the cursor below the image, so save and restore around it
+        Protocol::Sixel => format!("\x1b7{}\x1b8", sequence),
+    };
+
+    let prefix = " ".repeat(spacing);
+    let blank = " ".repeat(cols);
+    let mut lines = vec![format!("{}{}{}", prefix, sequence, blank)];
+    lines.extend((1..rows).map(|_| format!("{}{}", prefix, blank)));
+    Ok(lines)
+}
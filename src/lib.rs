@@ -0,0 +1,840 @@
+// system detection behind the rfetch binary, usable on its own:
+// SystemInfo::collect() gathers every field, to_value() turns it into
+// a structured value for serialization
+mod cache;
+pub use cache::{CacheOptions, default_cache_path};
+mod helpers;
+pub use helpers::*;
+
+pub mod output;
+mod system;
+pub use system::*;
+
+use std::{
+    env,
+    net::{IpAddr, Ipv4Addr},
+    panic,
+    sync::{Mutex, PoisonError},
+    thread::{self, Scope, ScopedJoinHandle},
+    time::{Duration, Instant},
+};
+
+use output::Value;
+
+#[derive(Clone, Copy)]
+pub struct Usage {
+    pub used: u64,
+    pub total: u64,
+    // disks only, what non-root users can still write. the root reserve is
+    // neither used nor available, so like df the percentage leaves it out
+    pub avail: Option<u64>,
+}
+
+impl Usage {
+    // what the percentage and bar are taken against
+    pub fn capacity(self) -> u64 {
+        match self.avail {
+            Some(avail) => self.used.saturating_add(avail),
+            None => self.total,
+        }
+    }
+
+    // used/capacity as 0-100, 0 when there's nothing to use (e.g. no swap).
+    // disks round up, as df does, so a nearly full one never reads 99%
+    pub fn percent(self) -> u64 {
+        let capacity = self.capacity() as u128;
+        if capacity == 0 {
+            return 0;
+        }
+        let scaled = self.used as u128 * 100;
+        let percent = if self.avail.is_some() {
+            scaled.div_ceil(capacity)
+        } else {
+            scaled / capacity
+        };
+        percent.min(100) as u64
+    }
+
+    fn to_value(self) -> Value {
+        Value::Object(vec![
+            ("used".into(), Value::Number(self.used)),
+            ("total".into(), Value::Number(self.total)),
+        ])
+    }
+}
+
+#[derive(Default)]
+pub struct Cpu {
+    pub model: String,
+    pub cores: Option<usize>,
+    pub threads: Option<usize>,
+    // distinct physical ids, None where cpuinfo has none (arm, some vms)
+    pub sockets: Option<usize>,
+    pub max_freq_mhz: Option<u64>,
+}
+
+// percent busy between two /proc/stat samples, overall and per core
+pub struct CpuUsage {
+    pub total: u64,
+    pub cores: Vec<u64>,
+}
+
+// bytes per second received and sent on one interface, sampled over
+// CollectOptions::net_sample_ms
+pub struct NetSpeed {
+    pub iface: String,
+    pub rx: u64,
+    pub tx: u64,
+}
+
+// uname's sysname and release, e.g. "Linux" and "6.6.8-arch1-1"
+pub struct Kernel {
+    pub name: String,
+    pub release: String,
+}
+
+pub struct Disk {
+    pub mount: String,
+    pub usage: Usage,
+}
+
+// e.g. nvme0 or sda
+pub struct DiskTemp {
+    pub device: String,
+    pub celsius: f64,
+}
+
+pub struct LocalIp {
+    pub addr: IpAddr,
+    pub iface: String,
+}
+
+// nameservers from resolv.conf; when that only points at systemd-resolved's
+// stub listener, stub is set and servers are its upstream ones (or the stub
+// address itself when those can't be read)
+pub struct Dns {
+    pub servers: Vec<String>,
+    pub stub: bool,
+}
+
+// the associated wireless network; signal is the link quality in percent
+pub struct Wifi {
+    pub iface: String,
+    pub ssid: Option<String>,
+    pub signal: Option<u8>,
+}
+
+impl Wifi {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("iface".into(), Value::Str(self.iface.clone())),
+            ("ssid".into(), opt_str(&self.ssid)),
+            ("signal".into(), opt_num(self.signal.map(u64::from))),
+        ])
+    }
+}
+
+pub struct Desktop {
+    pub name: String,
+    pub version: Option<String>,
+    // a full desktop environment rather than a bare window manager
+    pub is_de: bool,
+}
+
+impl Desktop {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("name".into(), Value::Str(self.name.clone())),
+            ("version".into(), opt_str(&self.version)),
+            (
+                "kind".into(),
+                Value::Str(if self.is_de { "de" } else { "wm" }.into()),
+            ),
+        ])
+    }
+}
+
+// memory modules as described by the SMBIOS type 17 entries
+pub struct RamHardware {
+    // e.g. "DDR4"
+    pub kind: String,
+    // MT/s of the most common module, which is what vendors sell as MHz
+    pub speed: Option<u32>,
+    pub populated: usize,
+    pub slots: usize,
+    // bytes, the sum of the module sizes
+    pub size: Option<u64>,
+}
+
+// the zram devices in use as swap, summed: compressed is what the stored
+// pages take up in ram, original their size before compression
+pub struct Zram {
+    pub compressed: u64,
+    pub original: u64,
+    // vm.swappiness, which zram setups usually raise above the default 60
+    pub swappiness: Option<u64>,
+}
+
+// physical memory in bytes; without the smbios table this is MemTotal, which
+// leaves out what the firmware and kernel reserve
+pub struct InstalledRam {
+    pub bytes: u64,
+    pub from_smbios: bool,
+}
+
+impl RamHardware {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("kind".into(), Value::Str(self.kind.clone())),
+            ("speed".into(), opt_num(self.speed.map(u64::from))),
+            ("populated".into(), Value::Number(self.populated as u64)),
+            ("slots".into(), Value::Number(self.slots as u64)),
+            ("size".into(), opt_num(self.size)),
+        ])
+    }
+}
+
+// driver is the bound kernel module, vram is in bytes
+pub struct Gpu {
+    pub name: String,
+    pub driver: Option<String>,
+    pub vram: Option<u64>,
+}
+
+impl Gpu {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("name".into(), Value::Str(self.name.clone())),
+            ("driver".into(), opt_str(&self.driver)),
+            ("vram".into(), opt_num(self.vram)),
+        ])
+    }
+}
+
+// one connected monitor; refresh is the active (xrandr) or preferred (edid)
+// rate in Hz
+pub struct Display {
+    pub mode: String,
+    pub refresh: Option<u32>,
+}
+
+impl Display {
+    fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("mode".into(), Value::Str(self.mode.clone())),
+            ("refresh".into(), opt_num(self.refresh.map(u64::from))),
+        ])
+    }
+}
+
+pub struct Battery {
+    pub capacity: u64,
+    pub status: String,
+}
+
+fn opt_str(value: &Option<String>) -> Value {
+    value.clone().map(Value::Str).unwrap_or(Value::Null)
+}
+
+fn opt_num(value: Option<u64>) -> Value {
+    value.map(Value::Number).unwrap_or(Value::Null)
+}
+
+// every field is collected exactly once as a raw value, the binary lays them
+// out next to the logo and to_value() serializes them
+pub struct SystemInfo {
+    pub user: String,
+    pub hostname: String,
+    pub uptime: Option<Duration>,
+    pub loadavg: Option<[f64; 3]>,
+    pub os: OsRelease,
+    // machine model from DMI
+    pub host: Option<String>,
+    // bare metal, a vm or a container
+    pub host_type: String,
+    pub board: Option<String>,
+    pub bios: Option<String>,
+    // the WSL version (1 or 2) when running under windows
+    pub wsl: Option<u8>,
+    pub cpu: Cpu,
+    pub cpu_temp: Option<f64>,
+    // sampled over CollectOptions::cpu_sample_ms
+    pub cpu_usage: Option<CpuUsage>,
+    pub gpus: Vec<Gpu>,
+    // celsius, one per gpu that has a sensor
+    pub gpu_temps: Vec<f64>,
+    pub packages: Vec<(&'static str, usize)>,
+    pub kernel: Option<Kernel>,
+    pub arch: Option<String>,
+    pub init: Option<String>,
+    // just / (and C: under WSL) unless CollectOptions::all_disks is set
+    pub disks: Vec<Disk>,
+    pub disk_temps: Vec<DiskTemp>,
+    pub memory: Option<Usage>,
+    // needs a readable SMBIOS table, usually root only
+    pub ram: Option<RamHardware>,
+    pub ram_installed: Option<InstalledRam>,
+    pub swap: Option<Usage>,
+    // None unless some swap is on zram
+    pub zram: Option<Zram>,
+    pub battery: Vec<Battery>,
+    // mains adapter plugged in, only on machines with a battery
+    pub ac: Option<bool>,
+    // percent, the backlight with the highest max_brightness
+    pub brightness: Option<u64>,
+    pub terminal: Option<String>,
+    pub font: Option<String>,
+    pub shell: Option<String>,
+    pub editor: Option<String>,
+    pub wm: Option<Desktop>,
+    // Wayland, X11 or TTY
+    pub session: Option<String>,
+    // gtk/qt widget theme
+    pub theme: Option<String>,
+    pub icons: Option<String>,
+    pub cursor: Option<String>,
+    // logged in users (distinct names) and our own terminal
+    pub users: usize,
+    pub tty: Option<String>,
+    pub processes: Option<usize>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    // seconds since the unix epoch when collected
+    pub time: Option<u64>,
+    pub displays: Vec<Display>,
+    // None on machines without any audio
+    pub audio: Option<String>,
+    pub local_ip: Option<LocalIp>,
+    // of the interface local_ip picks
+    pub mac: Option<String>,
+    pub gateway: Option<Ipv4Addr>,
+    pub dns: Option<Dns>,
+    // None when no wireless interface is associated
+    pub wifi: Option<Wifi>,
+    // None when the interface had no traffic during the sample
+    pub net_speed: Option<NetSpeed>,
+    // only looked up when CollectOptions::public_ip is set
+    pub public_ip: Option<IpAddr>,
+}
+
+// knobs that change what gets collected, as opposed to how it's displayed
+#[derive(Default)]
+pub struct CollectOptions {
+    // only report the local ip of this interface
+    pub iface: Option<String>,
+    // allow the network round trip for the public ip
+    pub public_ip: bool,
+    // report every mounted filesystem instead of only /
+    pub all_disks: bool,
+    // how long cpu usage is sampled for, DEFAULT_CPU_SAMPLE_MS when None
+    pub cpu_sample_ms: Option<u64>,
+    // same for the network throughput, DEFAULT_NET_SAMPLE_MS when None
+    pub net_sample_ms: Option<u64>,
+    // run every collector on the calling thread, one after another
+    pub single_threaded: bool,
+    // only collect these field keys (the binary's --fields names), None
+    // collects all but OPT_IN_FIELDS; os is always read since the logo
+    // depends on it
+    pub fields: Option<Vec<String>>,
+    // when set, every collector that runs adds how long it took (keyed like
+    // fields) for --timings
+    pub timings: Option<Mutex<Vec<(&'static str, Duration)>>>,
+    // reuse the slow fields from an earlier run, see cache.rs
+    pub cache: Option<CacheOptions>,
+}
+
+// (field, what it's worked out from), so asking for the one collects both
+static DERIVED_FIELDS: [(&str, &str); 2] =
+    [("ram_installed", "ram_type"), ("ram_installed", "memory")];
+
+// only collected when CollectOptions::fields names them: the samplers, which
+// each add a delay, and the cursor and icon themes
+pub static OPT_IN_FIELDS: [&str; 4] = ["cpu_usage", "icons", "cursor", "net_speed"];
+
+impl CollectOptions {
+    pub fn wants(&self, key: &str) -> bool {
+        match &self.fields {
+            Some(keys) => keys
+                .iter()
+                .any(|k| k == key || DERIVED_FIELDS.contains(&(k.as_str(), key))),
+            None => !OPT_IN_FIELDS.contains(&key),
+        }
+    }
+}
+
+// a collector that's either running on its own thread or already done
+enum Task<'scope, T> {
+    Spawned(ScopedJoinHandle<'scope, T>),
+    Done(T),
+}
+
+impl<T> Task<'_, T> {
+    fn join(self) -> T {
+        match self {
+            Task::Spawned(handle) => handle
+                .join()
+                .unwrap_or_else(|payload| panic::resume_unwind(payload)),
+            Task::Done(value) => value,
+        }
+    }
+}
+
+fn timed<T>(options: &CollectOptions, key: &'static str, collector: impl FnOnce() -> T) -> T {
+    let Some(timings) = &options.timings else {
+        return collector();
+    };
+    let start = Instant::now();
+    let value = collector();
+    timings
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push((key, start.elapsed()));
+    value
+}
+
+// skipped fields are left at their empty default
+fn task<'scope, T: Send + Default + 'scope>(
+    scope: &'scope Scope<'scope, '_>,
+    options: &'scope CollectOptions,
+    key: &'static str,
+    collector: impl FnOnce() -> T + Send + 'scope,
+) -> Task<'scope, T> {
+    if !options.wants(key) {
+        Task::Done(T::default())
+    } else if options.single_threaded {
+        Task::Done(timed(options, key, collector))
+    } else {
+        Task::Spawned(scope.spawn(move || timed(options, key, collector)))
+    }
+}
+
+// a value from the cache instead of running the collector
+fn cached_task<'scope, T: Send + Default + 'scope>(
+    scope: &'scope Scope<'scope, '_>,
+    options: &'scope CollectOptions,
+    key: &'static str,
+    cached: Option<T>,
+    collector: impl FnOnce() -> T + Send + 'scope,
+) -> Task<'scope, T> {
+    match cached {
+        Some(value) if options.wants(key) => Task::Done(value),
+        _ => task(scope, options, key, collector),
+    }
+}
+
+fn inline<T: Default>(
+    options: &CollectOptions,
+    key: &'static str,
+    collector: impl FnOnce() -> T,
+) -> T {
+    if options.wants(key) {
+        timed(options, key, collector)
+    } else {
+        T::default()
+    }
+}
+
+impl SystemInfo {
+    pub fn collect() -> Self {
+        Self::collect_with(&CollectOptions::default())
+    }
+
+    // the slow collectors (process spawns, sysfs walks, network) each get a
+    // thread, the cheap ones run inline while those are busy
+    pub fn collect_with(options: &CollectOptions) -> Self {
+        let cache = options
+            .cache
+            .as_ref()
+            .map(cache::Cache::load)
+            .unwrap_or_default();
+        let info = thread::scope(|s| {
+            let host = cached_task(s, options, "host", cache.host(), get_host_model);
+            let host_type = cached_task(s, options, "host_type", cache.host_type(), get_host_type);
+            let cpu = cached_task(s, options, "cpu", cache.cpu(), get_cpu_details);
+            let cpu_temp = task(s, options, "cpu_temp", get_cpu_temp);
+            let gpu_temps = task(s, options, "gpu_temp", get_gpu_temps);
+            let cpu_usage = task(s, options, "cpu_usage", || {
+                get_cpu_usage(Duration::from_millis(
+                    options.cpu_sample_ms.unwrap_or(DEFAULT_CPU_SAMPLE_MS),
+                ))
+            });
+            let gpus = cached_task(s, options, "gpu", cache.gpus(), get_gpu);
+            let packages = cached_task(s, options, "packages", cache.packages(), get_packages);
+            let disks = task(s, options, "disk", || {
+                if options.all_disks {
+                    get_all_disks()
+                } else {
+                    get_root_disks()
+                }
+            });
+            let shell = cached_task(s, options, "shell", cache.shell(), get_shell);
+            let wm = cached_task(s, options, "wm", cache.wm(), get_desktop);
+            let ram = cached_task(s, options, "ram_type", cache.ram(), get_ram_hardware);
+            let audio = task(s, options, "audio", get_sound_server);
+            let battery = task(s, options, "battery", get_battery);
+            let displays = cached_task(s, options, "resolution", cache.displays(), get_displays);
+            let local_ip = task(s, options, "local_ip", || {
+                get_local_ip(options.iface.as_deref())
+            });
+            let mac = task(s, options, "mac", || get_mac(options.iface.as_deref()));
+            let wifi = task(s, options, "wifi", get_wifi);
+            let net_speed = task(s, options, "net_speed", || {
+                get_net_speed(
+                    options.iface.as_deref(),
+                    Duration::from_millis(options.net_sample_ms.unwrap_or(DEFAULT_NET_SAMPLE_MS)),
+                )
+            });
+            let cached_ip = cache.public_ip().filter(|_| options.public_ip);
+            let public_ip = cached_task(s, options, "public_ip", cached_ip, || {
+                if options.public_ip {
+                    get_public_ip()
+                } else {
+                    None
+                }
+            });
+
+            let ram = ram.join();
+            let memory = inline(options, "memory", memory_usage);
+            SystemInfo {
+                uptime: inline(options, "uptime", get_uptime),
+                loadavg: inline(options, "load", get_loadavg),
+                os: timed(options, "os", os_release),
+                kernel: inline(options, "kernel", get_kernel),
+                arch: inline(options, "arch", get_arch),
+                init: inline(options, "init", get_init),
+                memory,
+                swap: inline(options, "swap", swap_usage),
+                disk_temps: inline(options, "disk_temp", get_disk_temps),
+                zram: inline(options, "zram", get_zram),
+                ram_installed: inline(options, "ram_installed", || {
+                    get_ram_installed(ram.as_ref(), memory)
+                }),
+                ac: inline(options, "ac", get_ac_status),
+                brightness: inline(options, "brightness", get_brightness),
+                gateway: inline(options, "gateway", get_gateway),
+                dns: inline(options, "dns", get_dns),
+                terminal: inline(options, "terminal", || env::var("TERM").ok()),
+                font: inline(options, "font", get_terminal_font),
+                editor: inline(options, "editor", get_editor),
+                session: inline(options, "session", get_session_type),
+                theme: inline(options, "theme", get_theme),
+                icons: inline(options, "icons", get_icon_theme),
+                cursor: inline(options, "cursor", get_cursor_theme),
+                users: inline(options, "users", get_user_count),
+                tty: inline(options, "users", get_tty),
+                processes: inline(options, "processes", get_process_count),
+                locale: inline(options, "locale", get_locale),
+                timezone: inline(options, "timezone", get_timezone),
+                time: inline(options, "date", get_time),
+                user: inline(options, "title", get_username),
+                hostname: inline(options, "title", get_hostname),
+                host: host.join(),
+                host_type: host_type.join(),
+                board: inline(options, "board", get_board),
+                bios: inline(options, "bios", get_bios),
+                wsl: inline(options, "wsl", get_wsl),
+                cpu: cpu.join(),
+                cpu_temp: cpu_temp.join(),
+                cpu_usage: cpu_usage.join(),
+                gpus: gpus.join(),
+                gpu_temps: gpu_temps.join(),
+                packages: packages.join(),
+                disks: disks.join(),
+                shell: shell.join(),
+                wm: wm.join(),
+                ram,
+                audio: audio.join(),
+                battery: battery.join(),
+                displays: displays.join(),
+                local_ip: local_ip.join(),
+                mac: mac.join(),
+                wifi: wifi.join(),
+                net_speed: net_speed.join(),
+                public_ip: public_ip.join(),
+            }
+        });
+
+        if let Some(cache_options) = &options.cache {
+            cache.store(cache_options, &info, |key| {
+                options.wants(key) && (key != "public_ip" || options.public_ip)
+            });
+        }
+        info
+    }
+
+    pub fn to_value(&self) -> Value {
+        Value::Object(vec![
+            ("user".into(), Value::Str(self.user.clone())),
+            ("hostname".into(), Value::Str(self.hostname.clone())),
+            (
+                "uptime".into(),
+                self.uptime
+                    .map(|u| Value::Number(u.as_secs()))
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "loadavg".into(),
+                self.loadavg
+                    .map(|l| Value::List(l.iter().map(|&v| Value::Float(v)).collect()))
+                    .unwrap_or(Value::Null),
+            ),
+            ("os".into(), Value::Str(self.os.name.clone())),
+            ("host".into(), opt_str(&self.host)),
+            ("host_type".into(), Value::Str(self.host_type.clone())),
+            ("board".into(), opt_str(&self.board)),
+            ("bios".into(), opt_str(&self.bios)),
+            ("wsl".into(), opt_num(self.wsl.map(u64::from))),
+            (
+                "cpu".into(),
+                Value::Object(vec![
+                    ("model".into(), Value::Str(self.cpu.model.clone())),
+                    ("cores".into(), opt_num(self.cpu.cores.map(|n| n as u64))),
+                    (
+                        "threads".into(),
+                        opt_num(self.cpu.threads.map(|n| n as u64)),
+                    ),
+                    (
+                        "sockets".into(),
+                        opt_num(self.cpu.sockets.map(|n| n as u64)),
+                    ),
+                    ("max_freq_mhz".into(), opt_num(self.cpu.max_freq_mhz)),
+                ]),
+            ),
+            (
+                "cpu_temp".into(),
+                self.cpu_temp.map(Value::Float).unwrap_or(Value::Null),
+            ),
+            (
+                "gpu_temps".into(),
+                Value::List(self.gpu_temps.iter().copied().map(Value::Float).collect()),
+            ),
+            (
+                "disk_temps".into(),
+                Value::List(
+                    self.disk_temps
+                        .iter()
+                        .map(|disk| {
+                            Value::Object(vec![
+                                ("device".into(), Value::Str(disk.device.clone())),
+                                ("celsius".into(), Value::Float(disk.celsius)),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "cpu_usage".into(),
+                match &self.cpu_usage {
+                    Some(usage) => Value::Object(vec![
+                        ("total".into(), Value::Number(usage.total)),
+                        (
+                            "cores".into(),
+                            Value::List(usage.cores.iter().copied().map(Value::Number).collect()),
+                        ),
+                    ]),
+                    None => Value::Null,
+                },
+            ),
+            (
+                "gpus".into(),
+                Value::List(self.gpus.iter().map(Gpu::to_value).collect()),
+            ),
+            (
+                "packages".into(),
+                Value::Object(
+                    self.packages
+                        .iter()
+                        .map(|(manager, n)| (manager.to_string(), Value::Number(*n as u64)))
+                        .collect(),
+                ),
+            ),
+            (
+                "kernel".into(),
+                match &self.kernel {
+                    Some(kernel) => Value::Object(vec![
+                        ("name".into(), Value::Str(kernel.name.clone())),
+                        ("release".into(), Value::Str(kernel.release.clone())),
+                    ]),
+                    None => Value::Null,
+                },
+            ),
+            ("arch".into(), opt_str(&self.arch)),
+            ("init".into(), opt_str(&self.init)),
+            (
+                "disks".into(),
+                Value::List(
+                    self.disks
+                        .iter()
+                        .map(|d| {
+                            Value::Object(vec![
+                                ("mount".into(), Value::Str(d.mount.clone())),
+                                ("used".into(), Value::Number(d.usage.used)),
+                                ("total".into(), Value::Number(d.usage.total)),
+                                (
+                                    "avail".into(),
+                                    Value::Number(d.usage.avail.unwrap_or_default()),
+                                ),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "memory".into(),
+                self.memory.map(Usage::to_value).unwrap_or(Value::Null),
+            ),
+            (
+                "zram".into(),
+                match &self.zram {
+                    Some(zram) => Value::Object(vec![
+                        ("compressed".into(), Value::Number(zram.compressed)),
+                        ("original".into(), Value::Number(zram.original)),
+                        ("swappiness".into(), opt_num(zram.swappiness)),
+                    ]),
+                    None => Value::Null,
+                },
+            ),
+            (
+                "ram_installed".into(),
+                match &self.ram_installed {
+                    Some(ram) => Value::Object(vec![
+                        ("bytes".into(), Value::Number(ram.bytes)),
+                        (
+                            "source".into(),
+                            Value::Str(if ram.from_smbios { "smbios" } else { "meminfo" }.into()),
+                        ),
+                    ]),
+                    None => Value::Null,
+                },
+            ),
+            (
+                "ram".into(),
+                self.ram
+                    .as_ref()
+                    .map(RamHardware::to_value)
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "swap".into(),
+                self.swap.map(Usage::to_value).unwrap_or(Value::Null),
+            ),
+            (
+                "battery".into(),
+                Value::List(
+                    self.battery
+                        .iter()
+                        .map(|b| {
+                            Value::Object(vec![
+                                ("capacity".into(), Value::Number(b.capacity)),
+                                ("status".into(), Value::Str(b.status.clone())),
+                            ])
+                        })
+                        .collect(),
+                ),
+            ),
+            (
+                "ac".into(),
+                match self.ac {
+                    Some(true) => Value::Str("connected".into()),
+                    Some(false) => Value::Str("disconnected".into()),
+                    None => Value::Null,
+                },
+            ),
+            ("brightness".into(), opt_num(self.brightness)),
+            ("terminal".into(), opt_str(&self.terminal)),
+            ("font".into(), opt_str(&self.font)),
+            ("shell".into(), opt_str(&self.shell)),
+            ("editor".into(), opt_str(&self.editor)),
+            (
+                "wm".into(),
+                self.wm
+                    .as_ref()
+                    .map(Desktop::to_value)
+                    .unwrap_or(Value::Null),
+            ),
+            ("session".into(), opt_str(&self.session)),
+            ("theme".into(), opt_str(&self.theme)),
+            ("icons".into(), opt_str(&self.icons)),
+            ("cursor".into(), opt_str(&self.cursor)),
+            ("users".into(), Value::Number(self.users as u64)),
+            ("tty".into(), opt_str(&self.tty)),
+            (
+                "processes".into(),
+                opt_num(self.processes.map(|n| n as u64)),
+            ),
+            ("locale".into(), opt_str(&self.locale)),
+            ("timezone".into(), opt_str(&self.timezone)),
+            ("time".into(), opt_num(self.time)),
+            (
+                "displays".into(),
+                Value::List(self.displays.iter().map(Display::to_value).collect()),
+            ),
+            ("audio".into(), opt_str(&self.audio)),
+            (
+                "local_ip".into(),
+                self.local_ip
+                    .as_ref()
+                    .map(|ip| {
+                        Value::Object(vec![
+                            ("addr".into(), Value::Str(ip.addr.to_string())),
+                            ("iface".into(), Value::Str(ip.iface.clone())),
+                        ])
+                    })
+                    .unwrap_or(Value::Null),
+            ),
+            ("mac".into(), opt_str(&self.mac)),
+            (
+                "gateway".into(),
+                self.gateway
+                    .map(|ip| Value::Str(ip.to_string()))
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "dns".into(),
+                self.dns
+                    .as_ref()
+                    .map(|dns| {
+                        Value::Object(vec![
+                            (
+                                "servers".into(),
+                                Value::List(dns.servers.iter().cloned().map(Value::Str).collect()),
+                            ),
+                            (
+                                "via".into(),
+                                if dns.stub {
+                                    Value::Str("systemd-resolved".into())
+                                } else {
+                                    Value::Null
+                                },
+                            ),
+                        ])
+                    })
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "wifi".into(),
+                self.wifi
+                    .as_ref()
+                    .map(Wifi::to_value)
+                    .unwrap_or(Value::Null),
+            ),
+            (
+                "net_speed".into(),
+                match &self.net_speed {
+                    Some(speed) => Value::Object(vec![
+                        ("iface".into(), Value::Str(speed.iface.clone())),
+                        ("rx".into(), Value::Number(speed.rx)),
+                        ("tx".into(), Value::Number(speed.tx)),
+                    ]),
+                    None => Value::Null,
+                },
+            ),
+            (
+                "public_ip".into(),
+                self.public_ip
+                    .map(|ip| Value::Str(ip.to_string()))
+                    .unwrap_or(Value::Null),
+            ),
+        ])
+    }
+}
@@ -0,0 +1,291 @@
+// built-in ascii logos, picked from the os-release ID (or ID_LIKE) when no
+// --config art is given
+
+pub struct Logo {
+    pub ids: &'static [&'static str],
+    pub color: &'static str,
+    pub art: &'static str,
+    // a compact version for --logo-size small
+    pub small: Option<&'static str>,
+}
+
+pub static GENERIC: Logo = Logo {
+    ids: &["linux"],
+    color: "0;37",
+    art: r#"        #####
+       #######
+       ##O#O##
+       #######
+     ###########
+    #############
+   ###############
+   ################
+  #################
+  #################
+#####################
+#####################
+  #################
+"#,
+    small: Some(
+        r#"    ___
+   (.. |
+   (<> |
+  / __  \
+ ( /  \ /|
+_/\ __)/_)
+\/-____\/
+"#,
+    ),
+};
+
+pub static LOGOS: &[Logo] = &[
+    Logo {
+        ids: &["arch", "archarm"],
+        color: "1;36",
+        art: r#"          /\
+         /  \
+        /    \
+       /      \
+      /   ,,   \
+     /   |  |   \
+    /_-''    ''-_\
+"#,
+        small: Some(
+            r#"   /\
+  /  \
+ /\   \
+/  --  \
+"#,
+        ),
+    },
+    Logo {
+        ids: &["debian"],
+        color: "1;31",
+        art: r#"     _____
+    /  __ \
+   |  /    |
+   |  \___-
+   -_
+     --_
+"#,
+        small: Some(
+            r#" _____
+/  __ \
+|  \_/
+ \_
+"#,
+        ),
+    },
+    Logo {
+        ids: &["ubuntu"],
+        color: "1;33",
+        art: r#"         _
+     ---(_)
+ _/  ---  \
+(_) |   |
+  \  --- _/
+     ---(_)
+"#,
+        small: Some(
+            r#"    (_)
+ _/ -- \
+(_)|  |
+  \ -- _
+    (_)
+"#,
+        ),
+    },
+    Logo {
+        ids: &["fedora"],
+        color: "1;34",
+        art: r#"        _____
+       /   __)\
+       |  /  \ \
+    ___|  |__/ /
+   / (_    _)_/
+  / /  |  |
+  \ \__/  |
+   \(_____/
+"#,
+        small: Some(
+            r#"   ____
+  / __)\
+ _| |_/ /
+/ (_ _)/
+\_| |
+"#,
+        ),
+    },
+    Logo {
+        ids: &["linuxmint"],
+        color: "1;32",
+        art: r#" _____________
+|_            \
+  |  | _____  |
+  |  | | | |  |
+  |  | | | |  |
+  |  \_____/  |
+  \___________/
+"#,
+        small: Some(
+            r#" ______
+|_     \
+ | |_| |
+ \_____/
+"#,
+        ),
+    },
+    Logo {
+        ids: &["manjaro"],
+        color: "1;32",
+        art: r#"||||||||| ||||
+||||||||| ||||
+||||      ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||
+|||| |||| ||||
+"#,
+        small: Some(
+            r#"|||| ||
+||   ||
+|| | ||
+|| | ||
+"#,
+        ),
+    },
+    Logo {
+        ids: &["opensuse", "opensuse-leap", "opensuse-tumbleweed", "suse"],
+        color: "1;32",
+        art: r#"  _______
+__|   __ \
+     / .\ \
+     \__/ |
+   _______|
+   \_______
+__________/
+"#,
+        small: Some(
+            r#" ____
+|  _ \
+  (_) |
+ ____/
+"#,
+        ),
+    },
+    Logo {
+        ids: &["gentoo"],
+        color: "1;35",
+        art: r#"   _-----_
+  (       \
+  \    0   \
+   \        )
+   /      _/
+  (     _-
+  \____-
+"#,
+        small: Some(
+            r#" _---_
+(  o  \
+ \    /
+ /_--
+"#,
+        ),
+    },
+    Logo {
+        ids: &["void"],
+        color: "1;32",
+        art: r#"    _______
+ _ \______ -
+| \  ___  \ |
+| | /   \ | |
+| | \___/ | |
+| \______ \_|
+ -_______\
+"#,
+        small: Some(
+            r#" _____
+| ___ \
+||   ||
+\ ___ |
+ -----
+"#,
+        ),
+    },
+    Logo {
+        ids: &["alpine"],
+        color: "1;34",
+        art: r#"   /\ /\
+  /  \  \
+ /    \  \
+/  /\  \  \
+\ /  \  \ /
+ /    \  \
+"#,
+        small: Some(
+            r#"  /\ /\
+ /  \  \
+/ /\ \  \
+"#,
+        ),
+    },
+    Logo {
+        ids: &["nixos"],
+        color: "1;34",
+        art: r#"  \\  \\ //
+ ==\\__\\/ //
+   //   \\//
+==//     //==
+ //\\___//
+// /\\  \\==
+  // \\  \\
+"#,
+        small: Some(
+            r#" \\ \\//
+==\\//\\==
+  //\\ \\
+"#,
+        ),
+    },
+];
+
+impl Logo {
+    // the small variant, or the full art with its blank lines dropped when
+    // there is none
+    pub fn small_art(&self) -> String {
+        match self.small {
+            Some(art) => art.to_string(),
+            None => self
+                .art
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| format!("{}\n", line))
+                .collect(),
+        }
+    }
+}
+
+pub fn find(id: &str) -> Option<&'static Logo> {
+    LOGOS.iter().find(|logo| logo.ids.contains(&id))
+}
+
+// every id a logo can be picked by, generic last
+pub fn all_ids() -> Vec<&'static str> {
+    LOGOS
+        .iter()
+        .chain([&GENERIC])
+        .flat_map(|logo| logo.ids.iter().copied())
+        .collect()
+}
+
+// for --ascii-distro, which can also ask for the generic logo
+pub fn by_id(id: &str) -> Option<&'static Logo> {
+    find(id).or_else(|| GENERIC.ids.contains(&id).then_some(&GENERIC))
+}
+
+// ID takes priority, then each ID_LIKE entry in order (e.g. "ubuntu debian")
+pub fn detect(id: &str, id_like: &str) -> &'static Logo {
+    find(id)
+        .or_else(|| id_like.split_whitespace().find_map(find))
+        .unwrap_or(&GENERIC)
+}
@@ -1,24 +1,21 @@
-use libc::{
-    AF_INET, c_char, freeifaddrs, gethostname, getifaddrs, ifaddrs, sockaddr_in, statvfs, sysinfo,
-    utsname,
-};
-
-use raw_cpuid::CpuId;
-
-use std::{
-    env,
-    ffi::{CStr, CString},
-    fs::{self, File},
-    io::{BufRead, BufReader},
-    net::Ipv4Addr,
-    ptr,
-    time::Duration,
-};
-
-mod helpers;
-pub use helpers::*;
+use std::env;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
+
+use rfetch::*;
+
+mod color;
+mod config;
+mod fields;
+mod image;
+mod logos;
+mod theme;
 
 static RESET_CODE: &str = "\x1b[0m";
+// spaces before the art when neither --spacing nor the config set it
+const DEFAULT_SPACING: usize = 3;
+// seconds the cache is good for with --once
+const DEFAULT_CACHE_TTL: u64 = 3600;
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
 fn help_menu() {
@@ -29,284 +26,1095 @@ USAGE:
     rfetch [OPTIONS]
 
 OPTIONS (optional):
-    --config <FILE>     path to text file containing ascii art
-    --spacing <N>       spaces before ASCII art (0–255, default=3)
-    --color <ANSI>      (e.g. 36, 1;36, 38;5;205)
+    --config <FILE>     path to config file (ascii art, optionally after a header),
+                        - reads it from stdin
+                        (default: $XDG_CONFIG_HOME/rfetch/config if it exists)
+    --spacing <N>       spaces before ASCII art (default=3)
+    --margin-top <N>, --margin-bottom <N>
+                        blank lines above and below the output (default=0)
+    --logo-side <left|right>
+                        which side of the info the ascii art goes on (default=left)
+    --align <top|center|bottom>
+                        line up the shorter of the art and the info with the
+                        other's top, middle or bottom (default=top)
+    --logo-size <full|small>
+                        compact built-in logos for small terminals (default=full)
+    --layout <auto|side|stacked>
+                        art next to the info or above it, auto stacks when the
+                        terminal is too narrow (default=auto)
+    --width <N>         terminal width to fit the output to (default: detected,
+                        no limit when piped)
+    --color <COLOR>     ANSI code, rgb or name (e.g. 36, 1;36, 38;5;205, #ff8800,
+                        255,136,0, red, bright_blue)
+    --gradient <START> <END>
+                        fade the ascii art between two rgb colors (not with --color)
+    --theme <NAME>      logo, separator and label colors from a preset: nord,
+                        gruvbox or dracula (--color still sets the logo)
+    --iface <NAME>      interface to report the local ip of
+    --disks <all|root>  show every mounted filesystem or just / (default=root)
+    --public-ip         look up the public ip (needs network access)
+    --no-threads        collect everything on one thread (for debugging)
+    --cache <SECS>      reuse slow fields (gpu, packages, shell, ...) from runs
+                        less than SECS ago, kept in $XDG_CACHE_HOME/rfetch/cache
+    --once              the same as --cache 3600
+    --no-cache          don't use the cache, even if the config turns it on
+    --refresh           collect everything again and rewrite the cache
+    --fields <KEYS>     only show these fields, in this order (e.g. cpu,memory,uptime)
+    --exclude <KEYS>    hide these fields (e.g. swap,local_ip)
+    --no-logo           print only the info, without ascii art
+    --preview           print only the art (with --config, --color, ...) and
+                        collect nothing, for working on a logo
+    --ascii-distro <ID> use the built-in logo of another distro (e.g. arch, debian)
+    --image <FILE>      show a png (kitty, wezterm, ghostty) or a sixel file (foot,
+                        mlterm, ...) instead of the ascii art when the terminal can
+    --json              print collected info as JSON (no ascii art)
+    --format <text|json|yaml|kv|oneline>
+                        output format, yaml has the same fields as json, kv
+                        prints key='value' lines for shell scripts, oneline short
+                        values on a single line for status bars (default=text)
+    --oneline           same as --format oneline
+    --no-palette        hide the terminal color blocks
+    --ascii-colors <LIST>
+                        which colors the blocks show, in order (e.g. 1,2,3,4,5,6,
+                        default=0-15)
+    --no-color          disable all colors (also when NO_COLOR is set)
+    --ascii-only        plain ascii degree signs, arrows and truncation marks, and
+                        box drawing in custom art, separators and labels
+                        downgraded, for terminals without unicode
+    --fahrenheit        show temperatures in °F instead of °C
+    --percent-only      show memory, swap and disk as just a percentage
+    --hide-disabled-swap
+                        leave out the swap line instead of \"Swap: disabled\"
+    --kernel-short      show just the kernel release (6.6.8-arch1-1), without \"Linux\"
+    --mask-mac          hide the last three octets of the mac address
+    --si                use powers of 1000 (GB) instead of 1024 (GiB)
+    --precision <N>     decimals of memory, swap and disk sizes (default=2)
+    --trim-zeros        leave out decimals that are all zero (15 GiB, not 15.00 GiB)
+    --bars              show memory, swap and disk as usage bars
+    --bar-width <N>     width of the usage bars (default=10)
+    --separator-char <C>
+                        what the line under the title is made of (default=-)
+    --separator-length <N>
+                        separator width in columns (default: as wide as the title)
+    --label-sep <STR>   what goes between labels and values, e.g. \"  \" or \" ❯ \"
+                        (default=\": \")
+    --cpu-sample <MS>   how long the cpu_usage field samples for (default=100)
+    --net-sample <MS>   how long the net_speed field samples for (default=200)
+    --list-fields       print every field key with a description and exit
     -h, --help          print help
-    -v, --version       print version"
-    );
-}
+    -V, --version       print version
 
-pub fn memory_usage() -> String {
-    let values = read_meminfo_fields(&["MemTotal:", "MemAvailable:"]);
-    let total = values.get(0).copied().unwrap_or(0);
-    let available = values.get(1).copied().unwrap_or(0);
-
-    let used = total.saturating_sub(available);
-    format!(
-        "Memory: {}/{}",
-        format_bytes(used * 1024),
-        format_bytes(total * 1024)
-    )
-}
-
-pub fn swap_usage() -> String {
-    let values = read_meminfo_fields(&["SwapTotal:", "SwapFree:"]);
-    let total = values.get(0).copied().unwrap_or(0);
-    let free = values.get(1).copied().unwrap_or(0);
-
-    let used = total.saturating_sub(free);
-    format!(
-        "Swap: {}/{}",
-        format_bytes(used * 1024),
-        format_bytes(total * 1024)
-    )
-}
+EXAMPLES:
+    rfetch --color bright_blue --spacing 0
+    rfetch --fields title,separator,os,cpu,memory --bars
+    rfetch --json --public-ip
 
-fn get_root_disk_usage() -> String {
-    let path = "/";
-    let c_path = CString::new(path).unwrap();
-    let mut stat: statvfs = unsafe { std::mem::zeroed() };
-
-    let ret = unsafe { statvfs(c_path.as_ptr() as *const c_char, &mut stat) };
-    if ret != 0 {
-        return "Disk usage: unknown".to_string();
-    }
-
-    let total = stat.f_blocks * stat.f_frsize as u64;
-    let free = stat.f_bfree * stat.f_frsize as u64;
-    let used = total - free;
-
-    // convert bytes to gb
-    let total_gb = total / 1024 / 1024 / 1024;
-    let used_gb = used / 1024 / 1024 / 1024;
-
-    format!("Disk: {}GB/{}GB used (/)", used_gb, total_gb)
+EXIT STATUS:
+    0   success
+    1   unknown option, unexpected argument or invalid value"
+    );
 }
 
-fn read_os_release(path: &str) -> (String, String) {
-    let mut os_name = "Unknown OS".to_string();
-    let mut ansi_color = "0;37".to_string(); // default white
-    let mut found_name = false;
-    let mut found_color = false;
-
-    if let Ok(file) = File::open(path) {
-        let reader = BufReader::new(file);
-        for line in reader.lines().flatten() {
-            if !found_name && line.starts_with("PRETTY_NAME=") {
-                os_name = line["PRETTY_NAME=".len()..].trim_matches('"').to_string();
-                found_name = true;
-            } else if !found_color && line.starts_with("ANSI_COLOR=") {
-                ansi_color = line["ANSI_COLOR=".len()..].trim_matches('"').to_string();
-                found_color = true;
-            }
-            if found_name && found_color {
-                break; // stop early once we have both
+// {1}, {2}, ... switch to that entry of the art palette and {/} goes back to
+// the line's own color; markers for entries that don't exist are left as is
+fn apply_color_markers(line: &str, palette: &[String], line_code: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    let mut rest = line;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let marker = rest.find('}').map(|end| &rest[1..end]);
+        let code = match marker {
+            Some("/") if line_code.is_empty() => Some(String::new()),
+            Some("/") => Some(format!("{}{}", RESET_CODE, line_code)),
+            Some(n) => n
+                .parse::<usize>()
+                .ok()
+                .and_then(|n| palette.get(n.checked_sub(1)?))
+                // reset first so e.g. bold from the line color doesn't carry over
+                .map(|code| match code.is_empty() {
+                    true => String::new(),
+                    false => format!("{}{}", RESET_CODE, code),
+                }),
+            None => None,
+        };
+        match (code, marker) {
+            (Some(code), Some(marker)) => {
+                out.push_str(&code);
+                rest = &rest[marker.len() + 2..];
+            }
+            _ => {
+                out.push('{');
+                rest = &rest[1..];
             }
         }
     }
-
-    (os_name, ansi_color)
+    out.push_str(rest);
+    out
 }
 
-fn color_ascii_art(ascii_art: &str, color_code: &str, spacing: u8) -> Vec<String> {
-    let prefix = " ".repeat(spacing as usize);
+// line i uses color_codes[i], the last code is reused once they run out;
+// palette holds the escapes for the {N} markers (empty strings without
+// colors), None for the built-in logos, which have no markers and may well
+// contain braces
+fn color_ascii_art(
+    ascii_art: &str,
+    color_codes: &[String],
+    palette: Option<&[String]>,
+    spacing: usize,
+) -> Vec<String> {
+    let prefix = " ".repeat(spacing);
     ascii_art
         .lines()
-        .map(|line| format!("{}{}{}{}", prefix, color_code, line, RESET_CODE))
+        .enumerate()
+        .map(|(i, line)| {
+            let color_code = color_codes
+                .get(i)
+                .or(color_codes.last())
+                .map(String::as_str)
+                .unwrap_or("");
+            let line = match palette {
+                Some(palette) => apply_color_markers(line, palette, color_code),
+                None => line.to_string(),
+            };
+            if color_code.is_empty() {
+                return format!("{}{}", prefix, line);
+            }
+            format!("{}{}{}{}", prefix, color_code, line, RESET_CODE)
+        })
         .collect()
 }
 
-fn get_user() -> String {
-    let username = env::var("USER").unwrap_or_else(|_| "unknown".into());
-    let hostname = unsafe {
-        let mut buf = [0u8; 256];
-        if gethostname(buf.as_mut_ptr() as *mut i8, buf.len()) == 0 {
-            CStr::from_ptr(buf.as_ptr() as *const i8)
-                .to_string_lossy()
-                .split('.')
-                .next()
-                .unwrap_or("unknown")
-                .to_string()
-        } else {
-            "unknown".into()
-        }
+// $XDG_CONFIG_HOME/rfetch/config, falling back to ~/.config/rfetch/config
+fn default_config_path() -> Option<String> {
+    let config_dir = match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => dir,
+        _ => format!("{}/.config", env::var("HOME").ok()?),
     };
-
-    // Append product name
-    let model = fs::read_to_string("/sys/class/dmi/id/product_name")
-        .unwrap_or_else(|_| "unknown".into())
-        .trim()
-        .to_string();
-
-    format!("{}@{}@{}", username, hostname, model)
+    let path = format!("{}/rfetch/config", config_dir);
+    std::path::Path::new(&path).is_file().then_some(path)
 }
 
-fn get_cpu() -> String {
-    CpuId::new()
-        .get_processor_brand_string()
-        .map(|b| b.as_str().to_string())
-        .unwrap_or_else(|| "Unknown CPU".to_string())
+// widest art line without its spacing prefix
+fn art_width(ascii_lines: &[String], spacing: usize) -> usize {
+    ascii_lines
+        .iter()
+        .map(|line| display_width(line).saturating_sub(spacing))
+        .max()
+        .unwrap_or(0)
 }
 
-fn get_kernel() -> String {
-    unsafe {
-        let mut uts: utsname = std::mem::zeroed();
-        if libc::uname(&mut uts) == 0 {
-            let sysname = CStr::from_ptr(uts.sysname.as_ptr()).to_string_lossy();
-            let release = CStr::from_ptr(uts.release.as_ptr()).to_string_lossy();
-            format!("KERNEL: {} {}", sysname, release)
-        } else {
-            "KERNEL: unknown".to_string()
+// --timings: one line per field key on stderr, slowest first; keys with
+// several collectors (title, users) add theirs up
+fn print_timings(timings: Vec<(&'static str, Duration)>) {
+    let mut totals: Vec<(&str, Duration)> = Vec::new();
+    for (key, elapsed) in timings {
+        match totals.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, total)) => *total += elapsed,
+            None => totals.push((key, elapsed)),
         }
     }
+    totals.sort_by_key(|&(_, total)| std::cmp::Reverse(total));
+    let width = totals.iter().map(|(key, _)| key.len()).max().unwrap_or(0);
+    for (key, total) in totals {
+        eprintln!(
+            "{:<width$}  {:>8.2} ms",
+            key,
+            total.as_secs_f64() * 1000.0,
+            width = width
+        );
+    }
 }
 
-fn get_local_ip() -> String {
-    unsafe {
-        let mut ifap: *mut ifaddrs = ptr::null_mut();
-        if getifaddrs(&mut ifap) != 0 {
-            return "Local IP: unknown".to_string();
-        }
+// total width print_stuff needs for art and info next to each other
+fn side_by_side_width(ascii_lines: &[String], sys_info: &[String], spacing: usize) -> usize {
+    let info_width = sys_info.iter().map(|l| display_width(l)).max().unwrap_or(0);
+    art_width(ascii_lines, spacing) + 5 + spacing + info_width
+}
+
+fn print_stuff(
+    ascii_lines: &[String],
+    sys_info: &[String],
+    spacing: usize,
+    side: LogoSide,
+    align: Align,
+) {
+    let max_lines = ascii_lines.len().max(sys_info.len());
+    // the shorter column starts this many lines down
+    let lead = |lines: &[String]| match align {
+        Align::Top => 0,
+        Align::Center => (max_lines - lines.len()) / 2,
+        Align::Bottom => max_lines - lines.len(),
+    };
+    let (art_lead, info_lead) = (lead(ascii_lines), lead(sys_info));
+    let line = |lines: &[String], lead: usize, i: usize| {
+        i.checked_sub(lead)
+            .and_then(|i| lines.get(i))
+            .cloned()
+            .unwrap_or_default()
+    };
 
-        let mut ptr_ifap = ifap;
-        while !ptr_ifap.is_null() {
-            let ifa = &*ptr_ifap;
-            if !ifa.ifa_addr.is_null() && (*ifa.ifa_addr).sa_family as i32 == AF_INET {
-                let sa = &*(ifa.ifa_addr as *const sockaddr_in);
-                let ip = Ipv4Addr::from(u32::from_be(sa.sin_addr.s_addr));
-                if ip != Ipv4Addr::new(127, 0, 0, 1) {
-                    freeifaddrs(ifap);
-                    return format!("Local IP: {}", ip);
+    match side {
+        LogoSide::Left => {
+            let offset = art_width(ascii_lines, spacing) + 5 + spacing;
+            for i in 0..max_lines {
+                let art_line = line(ascii_lines, art_lead, i);
+                let spaces = " ".repeat(offset.saturating_sub(display_width(&art_line)));
+                println!("{}{}{}", art_line, spaces, line(sys_info, info_lead, i));
+            }
+        }
+        // the info column is padded to its widest line, the art (with its
+        // spacing) follows after the same 5 column gap
+        LogoSide::Right => {
+            let offset = sys_info.iter().map(|l| display_width(l)).max().unwrap_or(0) + 5;
+            for i in 0..max_lines {
+                let info_line = line(sys_info, info_lead, i);
+                let art_line = line(ascii_lines, art_lead, i);
+                if art_line.is_empty() {
+                    println!("{}", info_line);
+                    continue;
                 }
+                let spaces = " ".repeat(offset.saturating_sub(display_width(&info_line)));
+                println!("{}{}{}", info_line, spaces, art_line);
             }
-            ptr_ifap = ifa.ifa_next;
         }
-
-        freeifaddrs(ifap);
-        "Local IP: unknown".to_string()
     }
 }
 
-fn get_uptime() -> String {
-    unsafe {
-        let mut info: sysinfo = std::mem::zeroed();
-        if libc::sysinfo(&mut info) == 0 {
-            let duration = Duration::from_secs(info.uptime as u64);
-            let hours = duration.as_secs() / 3600;
-            let minutes = (duration.as_secs() % 3600) / 60;
-            format!("Uptime: {} hours, {} mins", hours, minutes)
-        } else {
-            "Uptime: unknown".to_string()
-        }
+// the whole art, then the info under it after a blank line (just the info
+// with --no-logo)
+fn print_stacked(ascii_lines: &[String], sys_info: &[String]) {
+    for line in ascii_lines {
+        println!("{}", line);
+    }
+    if !ascii_lines.is_empty() {
+        println!();
+    }
+    for line in sys_info {
+        println!("{}", line);
     }
 }
 
-fn print_stuff(ascii_lines: &[String], sys_info: &[String], spacing: u8) {
-    let max_ascii_len = ascii_lines
-        .iter()
-        .map(|line| line.len() - spacing as usize)
-        .max()
-        .unwrap_or(0);
-    let offset = max_ascii_len + 5 + spacing as usize;
+// split a comma separated flag value like "cpu,memory,uptime"
+pub fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+        .collect()
+}
 
-    let max_lines = ascii_lines.len().max(sys_info.len());
-    let empty: &str = "";
-    for i in 0..max_lines {
-        let art_line: &str = if i < ascii_lines.len() {
-            &ascii_lines[i]
-        } else {
-            empty
-        };
-        let info_line: &str = if i < sys_info.len() { &sys_info[i] } else { "" };
-        let spaces = " ".repeat(offset.saturating_sub(art_line.len()));
-        println!("{}{}{}", art_line, spaces, info_line);
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogoSide {
+    Left,
+    Right,
+}
+
+// where the shorter of the art and the info sits next to the taller one
+#[derive(Clone, Copy, PartialEq)]
+pub enum Align {
+    Top,
+    Center,
+    Bottom,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Yaml,
+    // key='value' lines for shell scripts
+    Kv,
+    // the values on one line, for status bars
+    Oneline,
+}
+
+impl Format {
+    // json and yaml report everything collected, without the config
+    fn is_structured(self) -> bool {
+        matches!(self, Format::Json | Format::Yaml)
     }
 }
 
-// main
+#[derive(Clone, Copy, PartialEq)]
+pub enum LogoSize {
+    Full,
+    Small,
+}
 
-fn main() {
-    let args: Vec<String> = env::args().collect();
+// what rfetch does once the arguments are read, anything but Fetch prints
+// and exits without collecting
+#[derive(Clone, Copy, PartialEq)]
+pub enum Action {
+    Fetch,
+    Help,
+    Version,
+    ListFields,
+}
 
-    if args.iter().any(|a| a == "--help" || a == "-h") {
-        help_menu();
-        return;
-    } else if args.iter().any(|a| a == "--version" || a == "-v") {
-        println!("rfetch {}", VERSION);
-        return;
+// auto stacks only when side by side wouldn't fit the terminal
+#[derive(Clone, Copy, PartialEq)]
+pub enum Layout {
+    Auto,
+    Side,
+    Stacked,
+}
+
+pub struct Options {
+    pub action: Action,
+    // None until the config had its say, see DEFAULT_SPACING
+    pub spacing: Option<usize>,
+    // blank lines above and below the art and info
+    pub margin_top: usize,
+    pub margin_bottom: usize,
+    pub logo_side: LogoSide,
+    pub align: Align,
+    pub logo_size: LogoSize,
+    pub layout: Layout,
+    // terminal columns, detected when not given
+    pub width: Option<usize>,
+    pub logo: bool,
+    // just the art, for working on a config
+    pub preview: bool,
+    pub image: Option<String>,
+    pub ascii_distro: Option<String>,
+    pub format: Format,
+    pub palette: bool,
+    // 256-color indices of the palette blocks
+    pub palette_colors: Vec<u8>,
+    pub use_color: bool,
+    // downgrade everything printed to plain ascii
+    pub ascii_only: bool,
+    pub fahrenheit: bool,
+    pub percent_only: bool,
+    pub hide_disabled_swap: bool,
+    pub kernel_short: bool,
+    pub mask_mac: bool,
+    // strftime format of the date field
+    pub date_format: String,
+    pub separator_char: Option<String>,
+    // between a field's label and its value
+    pub label_sep: String,
+    pub separator_length: Option<usize>,
+    pub cache_ttl: Option<u64>,
+    pub no_cache: bool,
+    pub refresh: bool,
+    pub units: Units,
+    // decimals of byte sizes, None until the config had its say (then
+    // DEFAULT_PRECISION)
+    pub precision: Option<usize>,
+    pub trim_zeros: bool,
+    pub bars: bool,
+    pub bar_width: usize,
+    pub collect: CollectOptions,
+    pub config_path: Option<String>,
+    pub color: Option<String>,
+    pub gradient: Option<(String, String)>,
+    pub theme: Option<String>,
+    pub fields: Option<Vec<String>>,
+    pub exclude: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            action: Action::Fetch,
+            spacing: None,
+            margin_top: 0,
+            margin_bottom: 0,
+            logo_side: LogoSide::Left,
+            align: Align::Top,
+            logo_size: LogoSize::Full,
+            layout: Layout::Auto,
+            width: None,
+            logo: true,
+            preview: false,
+            image: None,
+            ascii_distro: None,
+            format: Format::Text,
+            palette: true,
+            palette_colors: (0..16).collect(),
+            use_color: !no_color_env(),
+            ascii_only: false,
+            fahrenheit: false,
+            percent_only: false,
+            hide_disabled_swap: false,
+            kernel_short: false,
+            mask_mac: false,
+            date_format: "%Y-%m-%d %H:%M".to_string(),
+            separator_char: None,
+            label_sep: ": ".to_string(),
+            separator_length: None,
+            cache_ttl: None,
+            no_cache: false,
+            refresh: false,
+            units: Units::Binary,
+            precision: None,
+            trim_zeros: false,
+            bars: false,
+            bar_width: 10,
+            collect: CollectOptions::default(),
+            config_path: None,
+            color: None,
+            gradient: None,
+            theme: None,
+            fields: None,
+            exclude: Vec::new(),
+        }
     }
+}
 
-    let mut ascii_art = r#"        #####
-       #######
-       ##O#O##
-       #######
-     ###########
-    #############
-   ###############
-   ################
-  #################
-  #################
-#####################
-#####################
-  #################
-"#
-    .to_string();
-    let mut spacing: u8 = 3;
-
-    let (os_name, mut ansi_color) = if std::path::Path::new("/etc/os-release").exists() {
-        read_os_release("/etc/os-release")
-    } else if std::path::Path::new("/usr/lib/os-release").exists() {
-        read_os_release("/usr/lib/os-release")
-    } else {
-        ("Unknown OS".to_string(), "0;37".to_string())
-    };
+pub const DEFAULT_PRECISION: usize = 2;
+
+// the value after an option; a missing one is an error rather than the
+// option being silently dropped
+fn next_value<'a>(
+    iter: &mut impl Iterator<Item = &'a String>,
+    opt: &str,
+) -> Result<&'a str, String> {
+    iter.next()
+        .map(String::as_str)
+        .ok_or_else(|| format!("{} requires a value", opt))
+}
+
+// parse args manually, rfetch takes no positional arguments so anything
+// that isn't a known option (or its value) is an error
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
 
-    // parse args manually
     let mut iter = args.iter().skip(1); // skip program name
     while let Some(arg) = iter.next() {
         match arg.as_str() {
+            // handled here rather than by scanning args so that an option's
+            // value (--label-sep -h) isn't taken for one
+            "-h" | "--help" => {
+                options.action = Action::Help;
+                return Ok(options);
+            }
+            "-V" | "-v" | "--version" => {
+                options.action = Action::Version;
+                return Ok(options);
+            }
+            "--list-fields" => {
+                options.action = Action::ListFields;
+                return Ok(options);
+            }
             "--config" => {
-                if let Some(path) = iter.next() {
-                    ascii_art = read_file_trim(path);
-                }
+                options.config_path = Some(next_value(&mut iter, arg)?.to_string());
             }
             "--spacing" => {
-                if let Some(val) = iter.next() {
-                    if let Ok(num) = val.parse::<u8>() {
-                        spacing = num;
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(num) => options.spacing = Some(num),
+                    Err(_) => {
+                        return Err(format!(
+                            "--spacing expects a number of spaces, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--margin-top" | "--margin-bottom" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(num) if arg == "--margin-top" => options.margin_top = num,
+                    Ok(num) => options.margin_bottom = num,
+                    Err(_) => {
+                        return Err(format!("{} expects a number of lines, got '{}'", arg, val));
+                    }
+                }
+            }
+            "--logo-side" => match next_value(&mut iter, arg)? {
+                "left" => options.logo_side = LogoSide::Left,
+                "right" => options.logo_side = LogoSide::Right,
+                other => {
+                    return Err(format!(
+                        "--logo-side expects 'left' or 'right', got '{}'",
+                        other
+                    ));
+                }
+            },
+            "--align" => match next_value(&mut iter, arg)? {
+                "top" => options.align = Align::Top,
+                "center" => options.align = Align::Center,
+                "bottom" => options.align = Align::Bottom,
+                other => {
+                    return Err(format!(
+                        "--align expects 'top', 'center' or 'bottom', got '{}'",
+                        other
+                    ));
+                }
+            },
+            "--logo-size" => match next_value(&mut iter, arg)? {
+                "full" => options.logo_size = LogoSize::Full,
+                "small" => options.logo_size = LogoSize::Small,
+                other => {
+                    return Err(format!(
+                        "--logo-size expects 'full' or 'small', got '{}'",
+                        other
+                    ));
+                }
+            },
+            "--layout" => match next_value(&mut iter, arg)? {
+                "auto" => options.layout = Layout::Auto,
+                "side" => options.layout = Layout::Side,
+                "stacked" => options.layout = Layout::Stacked,
+                other => {
+                    return Err(format!(
+                        "--layout expects 'auto', 'side' or 'stacked', got '{}'",
+                        other
+                    ));
+                }
+            },
+            "--width" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(num) => options.width = Some(num),
+                    Err(_) => {
+                        return Err(format!(
+                            "--width expects a number of columns, got '{}'",
+                            val
+                        ));
                     }
                 }
             }
             "--color" => {
-                if let Some(c) = iter.next() {
-                    ansi_color = c.clone();
+                options.color = Some(next_value(&mut iter, arg)?.to_string());
+            }
+            "--theme" => {
+                options.theme = Some(next_value(&mut iter, arg)?.to_string());
+            }
+            "--gradient" => {
+                let start = next_value(&mut iter, arg)?;
+                let end = next_value(&mut iter, arg)?;
+                options.gradient = Some((start.to_string(), end.to_string()));
+            }
+            "--iface" => {
+                options.collect.iface = Some(next_value(&mut iter, arg)?.to_string());
+            }
+            "--fields" => {
+                options.fields = Some(parse_list(next_value(&mut iter, arg)?));
+            }
+            "--exclude" => {
+                options
+                    .exclude
+                    .extend(parse_list(next_value(&mut iter, arg)?));
+            }
+            "--disks" => match next_value(&mut iter, arg)? {
+                "all" => options.collect.all_disks = true,
+                "root" => options.collect.all_disks = false,
+                other => {
+                    return Err(format!("--disks expects 'all' or 'root', got '{}'", other));
+                }
+            },
+            "--public-ip" => options.collect.public_ip = true,
+            "--no-threads" => options.collect.single_threaded = true,
+            "--cache" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<u64>() {
+                    Ok(secs) => options.cache_ttl = Some(secs),
+                    Err(_) => {
+                        return Err(format!(
+                            "--cache expects a number of seconds, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--once" => options.cache_ttl = Some(DEFAULT_CACHE_TTL),
+            "--no-cache" => options.no_cache = true,
+            "--refresh" => options.refresh = true,
+            // not in --help, it's for working on rfetch itself
+            "--timings" => options.collect.timings = Some(Mutex::default()),
+            "--no-logo" => options.logo = false,
+            "--preview" => options.preview = true,
+            "--image" => {
+                options.image = Some(next_value(&mut iter, arg)?.to_string());
+            }
+            "--ascii-distro" => {
+                options.ascii_distro = Some(next_value(&mut iter, arg)?.to_lowercase());
+            }
+            "--json" => options.format = Format::Json,
+            "--oneline" => options.format = Format::Oneline,
+            "--format" => match next_value(&mut iter, arg)? {
+                "text" => options.format = Format::Text,
+                "json" => options.format = Format::Json,
+                "yaml" => options.format = Format::Yaml,
+                "kv" => options.format = Format::Kv,
+                "oneline" => options.format = Format::Oneline,
+                other => {
+                    return Err(format!(
+                        "--format expects 'text', 'json', 'yaml', 'kv' or 'oneline', got '{}'",
+                        other
+                    ));
+                }
+            },
+            "--no-palette" => options.palette = false,
+            "--ascii-colors" => {
+                let val = next_value(&mut iter, arg)?;
+                let indices: Result<Vec<u8>, _> =
+                    parse_list(val).iter().map(|i| i.parse()).collect();
+                match indices {
+                    Ok(indices) if !indices.is_empty() => options.palette_colors = indices,
+                    _ => {
+                        return Err(format!(
+                            "--ascii-colors expects color indices from 0 to 255, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--no-color" => options.use_color = false,
+            "--ascii-only" => options.ascii_only = true,
+            "--fahrenheit" => options.fahrenheit = true,
+            "--percent-only" => options.percent_only = true,
+            "--hide-disabled-swap" => options.hide_disabled_swap = true,
+            "--kernel-short" => options.kernel_short = true,
+            "--mask-mac" => options.mask_mac = true,
+            "--si" => options.units = Units::Decimal,
+            "--precision" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(digits) => options.precision = Some(digits),
+                    Err(_) => {
+                        return Err(format!(
+                            "--precision expects a number of decimals, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--trim-zeros" => options.trim_zeros = true,
+            "--bars" => options.bars = true,
+            "--cpu-sample" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<u64>() {
+                    Ok(ms) => options.collect.cpu_sample_ms = Some(ms),
+                    Err(_) => {
+                        return Err(format!(
+                            "--cpu-sample expects a number of milliseconds, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--net-sample" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<u64>() {
+                    Ok(ms) => options.collect.net_sample_ms = Some(ms),
+                    Err(_) => {
+                        return Err(format!(
+                            "--net-sample expects a number of milliseconds, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            "--separator-char" => {
+                options.separator_char = Some(next_value(&mut iter, arg)?.to_string());
+            }
+            "--label-sep" => {
+                options.label_sep = next_value(&mut iter, arg)?.to_string();
+            }
+            "--separator-length" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(len) => options.separator_length = Some(len),
+                    Err(_) => {
+                        return Err(format!(
+                            "--separator-length expects a number of columns, got '{}'",
+                            val
+                        ));
+                    }
                 }
             }
-            _ => {}
+            "--bar-width" => {
+                let val = next_value(&mut iter, arg)?;
+                match val.parse::<usize>() {
+                    Ok(num) if num > 0 => options.bar_width = num,
+                    _ => {
+                        return Err(format!(
+                            "--bar-width expects a number of cells above 0, got '{}'",
+                            val
+                        ));
+                    }
+                }
+            }
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(options)
+}
+
+// main
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    let mut options = parse_args(&args).unwrap_or_else(|err| {
+        eprintln!("error: {} (see --help)", err);
+        std::process::exit(1);
+    });
+    match options.action {
+        Action::Fetch => {}
+        Action::Help => {
+            help_menu();
+            return;
+        }
+        Action::Version => {
+            println!("rfetch {}", VERSION);
+            return;
+        }
+        Action::ListFields => {
+            fields::list_fields();
+            return;
+        }
+    }
+    if matches!(options.format, Format::Kv | Format::Oneline) {
+        options.use_color = false;
+    }
+    if options.format == Format::Oneline && options.fields.is_none() {
+        options.fields = Some(fields::ONELINE_KEYS.map(String::from).to_vec());
+    }
+    // the preview is about the art, which only the text output has
+    if options.preview {
+        options.format = Format::Text;
+    }
+    if let Some(keys) = &options.fields {
+        fields::warn_unknown_keys(keys);
+    }
+    fields::warn_unknown_keys(&options.exclude);
+
+    let forced_logo = options.ascii_distro.as_deref().map(|id| {
+        logos::by_id(id).unwrap_or_else(|| {
+            eprintln!(
+                "error: no built-in logo for '{}' (available: {})",
+                id,
+                logos::all_ids().join(", ")
+            );
+            std::process::exit(1);
+        })
+    });
+    let theme = options.theme.as_deref().map(|name| {
+        theme::find(name).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        })
+    });
+    let gradient = match &options.gradient {
+        Some(_) if options.color.is_some() => {
+            eprintln!("error: --gradient and --color can't be used together");
+            std::process::exit(1);
+        }
+        Some((start, end)) => match (color::parse_rgb(start), color::parse_rgb(end)) {
+            (Some(start), Some(end)) => Some((start, end)),
+            _ => {
+                eprintln!("error: --gradient expects two colors like #ff8800 or 255,136,0");
+                std::process::exit(1);
+            }
+        },
+        None => None,
+    };
+    let color_arg = options.color.as_ref().map(|c| {
+        color::parse_color(c).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        })
+    });
+
+    // json and yaml always report everything and don't look at the config;
+    // otherwise an explicit --config wins over the default location
+    // "-" reads it from stdin
+    let mut config = match options.config_path.clone().or_else(default_config_path) {
+        Some(path) if path == "-" && !options.format.is_structured() => match read_stdin_trim() {
+            Some(Ok(text)) => config::parse_config(&text),
+            Some(Err(err)) => {
+                eprintln!("error: cannot read config from stdin: {}", err);
+                std::process::exit(1);
+            }
+            None => {
+                eprintln!("warning: --config - expects the config on stdin, nothing was piped");
+                config::Config::default()
+            }
+        },
+        Some(path) if !options.format.is_structured() => match read_file_trim(&path) {
+            Ok(text) => config::parse_config(&text),
+            Err(err) => {
+                eprintln!("error: cannot read config '{}': {}", path, err);
+                std::process::exit(1);
+            }
+        },
+        _ => config::Config::default(),
+    };
+    fields::warn_unknown_keys(&config.order);
+    // rfetch's own glyphs follow --ascii-only by themselves, what the user
+    // wrote may need downgrading
+    if options.ascii_only {
+        config.art = config.art.as_deref().map(to_ascii);
+        for (_, label) in config.labels.iter_mut() {
+            *label = to_ascii(label);
+        }
+        config.separator_char = config.separator_char.as_deref().map(to_ascii);
+        options.separator_char = options.separator_char.as_deref().map(to_ascii);
+        options.label_sep = to_ascii(&options.label_sep);
+    }
+    options.percent_only |= config.percent_only.unwrap_or(false);
+    options.hide_disabled_swap |= config.hide_disabled_swap.unwrap_or(false);
+    options.kernel_short |= config.kernel_short.unwrap_or(false);
+    options.trim_zeros |= config.trim_zeros.unwrap_or(false);
+    if let Some(format) = &config.date_format {
+        options.date_format = format.clone();
+    }
+    // flags win over the config for these
+    options.separator_char = options.separator_char.or(config.separator_char.clone());
+    let spacing = options
+        .spacing
+        .or(config.spacing)
+        .unwrap_or(DEFAULT_SPACING);
+    options.separator_length = options.separator_length.or(config.separator_length);
+    options.precision = options.precision.or(config.precision);
+    if let Some(ttl_secs) = options.cache_ttl.or(config.cache_ttl)
+        && !options.no_cache
+        && let Some(path) = default_cache_path()
+    {
+        options.collect.cache = Some(CacheOptions {
+            path,
+            ttl_secs,
+            refresh: options.refresh,
+        });
+    }
+    if options.preview {
+        // only os-release is read, for the logo and its color
+        options.collect.fields = Some(Vec::new());
+    } else if !options.format.is_structured() {
+        options.collect.fields = Some(fields::active_keys(
+            options.fields.as_deref(),
+            &options.exclude,
+            &config.order,
+        ));
+    } else {
+        // every default field, and the opt-in ones only when asked for
+        let named = options.fields.as_deref().unwrap_or(&config.order);
+        options.collect.fields = Some(fields::active_keys(None, &[], named));
+    }
+
+    let info = SystemInfo::collect_with(&options.collect);
+    if let Some(timings) = options.collect.timings.take() {
+        print_timings(timings.into_inner().unwrap_or_else(PoisonError::into_inner));
+    }
+    match options.format {
+        Format::Json => {
+            println!("{}", info.to_value().to_json());
+            return;
+        }
+        Format::Yaml => {
+            print!("{}", info.to_value().to_yaml());
+            return;
+        }
+        Format::Text | Format::Kv | Format::Oneline => {}
+    }
+
+    let logo = forced_logo.unwrap_or_else(|| logos::detect(&info.os.id, &info.os.id_like));
+    let ascii_art = config
+        .art
+        .clone()
+        .unwrap_or_else(|| match options.logo_size {
+            LogoSize::Full => logo.art.to_string(),
+            LogoSize::Small => logo.small_art(),
+        });
+    // os-release's ANSI_COLOR belongs to the detected distro, not a forced logo
+    let ansi_color = color_arg
+        .or_else(|| theme.map(|theme| theme::sgr(theme.logo)))
+        .or_else(|| config.color.clone())
+        .or_else(|| info.os.ansi_color.clone().filter(|_| forced_logo.is_none()))
+        .unwrap_or_else(|| logo.color.to_string());
+
+    let color_code = if options.use_color {
+        format!("\x1b[{}m", ansi_color)
+    } else {
+        String::new()
+    };
+    let line_codes = match gradient {
+        _ if !options.use_color => Vec::new(),
+        Some((start, end)) => color::gradient_sgr(start, end, ascii_art.lines().count())
+            .iter()
+            .map(|sgr| format!("\x1b[{}m", sgr))
+            .collect(),
+        None => vec![color_code.clone()],
+    };
+    let theme_code = |pick: fn(&theme::Theme) -> (u8, u8, u8)| match theme {
+        Some(theme) if options.use_color => format!("\x1b[{}m", theme::sgr(pick(theme))),
+        _ => String::new(),
+    };
+    // labels are bold in the logo color unless a theme or the config says otherwise
+    let label_code = match theme_code(|theme| theme.label) {
+        _ if !options.use_color => String::new(),
+        code if !code.is_empty() => code,
+        _ => match &config.label_color {
+            Some(sgr) => format!("\x1b[{}m", sgr),
+            None => format!("\x1b[1m\x1b[{}m", ansi_color),
+        },
+    };
+    let separator_code = match theme_code(|theme| theme.separator) {
+        code if code.is_empty() => line_codes.first().unwrap_or(&color_code).clone(),
+        code => code,
+    };
+    let art_palette: Vec<String> = config
+        .colors
+        .iter()
+        .map(|sgr| match options.use_color {
+            true => format!("\x1b[{}m", sgr),
+            false => String::new(),
+        })
+        .collect();
+    let mut colored_art_lines = if options.logo {
+        let palette = config.art.as_ref().map(|_| art_palette.as_slice());
+        color_ascii_art(&ascii_art, &line_codes, palette, spacing)
+    } else {
+        Vec::new()
+    };
+    if options.preview {
+        for line in &colored_art_lines {
+            println!("{}", line);
+        }
+        return;
+    }
+
+    let separator = make_separator(
+        options
+            .separator_length
+            .unwrap_or_else(|| display_width(&fields::title(&info))),
+        options.separator_char.as_deref().unwrap_or("-"),
+        &separator_code,
+    );
+    let mut all_fields = fields::order_fields(
+        fields::build_fields(&info, &separator, &color_code, &options),
+        &config.order,
+    );
+    fields::relabel_fields(&mut all_fields, &config.labels);
+    let selected = fields::select_fields(all_fields, options.fields.as_deref(), &options.exclude);
+    match options.format {
+        Format::Kv => {
+            for line in fields::kv_lines(&selected) {
+                println!("{}", line);
+            }
+            return;
+        }
+        Format::Oneline => {
+            println!("{}", fields::oneline(&selected));
+            return;
         }
+        _ => {}
+    }
+    let mut sys_info: Vec<String> = selected
+        .iter()
+        .map(|field| field.render(&label_code, &options.label_sep))
+        .collect();
+    if options.palette && options.use_color {
+        sys_info.push(String::new());
+        sys_info.extend(color_blocks(&options.palette_colors));
+    }
+
+    // the ascii logo stays when the terminal can't show images
+    if let Some(path) = &options.image
+        && options.logo
+        && let Some(protocol) = image::detect_protocol()
+    {
+        match image::image_lines(path, protocol, sys_info.len(), spacing) {
+            Ok(lines) => colored_art_lines = lines,
+            Err(err) => eprintln!("warning: {}, showing the ascii logo instead", err),
+        }
+    }
+
+    let width = options.width.or_else(terminal_width);
+    let stacked = !options.logo
+        || match options.layout {
+            Layout::Side => false,
+            Layout::Stacked => true,
+            Layout::Auto => width.is_some_and(|width| {
+                side_by_side_width(&colored_art_lines, &sys_info, spacing) > width
+            }),
+        };
+    // whatever the art column leaves is what the info lines get
+    if let Some(width) = width {
+        let art_column = if stacked {
+            0
+        } else {
+            art_width(&colored_art_lines, spacing) + 5 + spacing
+        };
+        let available = width.saturating_sub(art_column);
+        for line in sys_info.iter_mut() {
+            *line = truncate_to_width(line, available, glyphs(options.ascii_only).ellipsis);
+        }
+    }
+    for _ in 0..options.margin_top {
+        println!();
+    }
+    if stacked {
+        print_stacked(&colored_art_lines, &sys_info);
+    } else {
+        print_stuff(
+            &colored_art_lines,
+            &sys_info,
+            spacing,
+            options.logo_side,
+            options.align,
+        );
+    }
+    for _ in 0..options.margin_bottom {
+        println!();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers_only_apply_to_custom_art() {
+        let palette = vec!["\x1b[31m".to_string()];
+        let art = "{1}a{/} {x}";
+        assert_eq!(
+            color_ascii_art(art, &[], Some(&palette), 0),
+            vec!["\x1b[0m\x1b[31ma {x}"]
+        );
+        assert_eq!(color_ascii_art(art, &[], None, 1), vec![" {1}a{/} {x}"]);
     }
 
-    let color_code = format!("\x1b[{}m", ansi_color);
-    let colored_art_lines = color_ascii_art(&ascii_art, &color_code, spacing);
-
-    let separator = make_separator(get_user().len(), &color_code);
-
-    let sys_info = vec![
-        get_user(),
-        get_uptime(),
-        separator.clone(),
-        format!("OS: {}", os_name),
-        format!("CPU: {}", get_cpu()),
-        get_kernel(),
-        get_root_disk_usage(),
-        memory_usage(),
-        swap_usage(),
-        format!("Terminal: {}", evod("TERM", "unknown")),
-        format!("Shell: {}", evod("SHELL", "unknown")),
-        format!("WM: {}", evod("XDG_CURRENT_DESKTOP", "unknown")),
-        get_local_ip(),
-    ];
-
-    print_stuff(&colored_art_lines, &sys_info, spacing);
+    #[test]
+    fn trailing_option_without_value_is_an_error() {
+        let args = |list: &[&str]| -> Vec<String> {
+            std::iter::once("rfetch")
+                .chain(list.iter().copied())
+                .map(String::from)
+                .collect()
+        };
+        for opt in ["--color", "--label-sep", "--fields", "--width"] {
+            assert_eq!(
+                parse_args(&args(&[opt])).err(),
+                Some(format!("{} requires a value", opt))
+            );
+        }
+        assert_eq!(
+            parse_args(&args(&["--gradient", "red"])).err(),
+            Some("--gradient requires a value".to_string())
+        );
+    }
+
+    #[test]
+    fn help_and_version_are_only_read_as_options() {
+        let args = |list: &[&str]| -> Vec<String> {
+            std::iter::once("rfetch")
+                .chain(list.iter().copied())
+                .map(String::from)
+                .collect()
+        };
+        let options = parse_args(&args(&["--label-sep", "-h"])).unwrap();
+        assert!(options.action == Action::Fetch);
+        assert_eq!(options.label_sep, "-h");
+        let options = parse_args(&args(&["--color", "red", "-V"])).unwrap();
+        assert!(options.action == Action::Version);
+    }
 }
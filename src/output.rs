@@ -0,0 +1,253 @@
+// structured representation of the collected fields, shared by the
+// machine-readable output formats
+pub enum Value {
+    Null,
+    Number(u64),
+    Float(f64),
+    Str(String),
+    List(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+fn json_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Value {
+    pub fn to_json(&self) -> String {
+        match self {
+            Value::Null => "null".to_string(),
+            Value::Number(n) => n.to_string(),
+            Value::Float(f) if f.is_finite() => format!("{:.2}", f),
+            Value::Float(_) => "null".to_string(),
+            Value::Str(s) => json_escape(s),
+            Value::List(items) => {
+                let items: Vec<String> = items.iter().map(Value::to_json).collect();
+                format!("[{}]", items.join(","))
+            }
+            Value::Object(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(key, value)| format!("{}:{}", json_escape(key), value.to_json()))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+}
+
+impl Value {
+    // block style yaml; strings use the json escaping, which yaml's double
+    // quoted scalars share, so nothing can be mistaken for a number or bool
+    pub fn to_yaml(&self) -> String {
+        let mut out = String::from("---\n");
+        match self {
+            Value::Object(fields) if !fields.is_empty() => {
+                write_yaml_fields(&mut out, fields, 0, false)
+            }
+            Value::List(items) if !items.is_empty() => write_yaml_items(&mut out, items, 0),
+            value => out.push_str(&format!("{}\n", value.yaml_scalar())),
+        }
+        out
+    }
+
+    // inline form of scalars and empty collections
+    fn yaml_scalar(&self) -> String {
+        match self {
+            Value::List(_) => "[]".to_string(),
+            Value::Object(_) => "{}".to_string(),
+            value => value.to_json(),
+        }
+    }
+}
+
+// one "key: value" line per field (keys are plain identifiers); the first
+// one goes after an already written "- " when it starts a list item
+fn write_yaml_fields(out: &mut String, fields: &[(String, Value)], indent: usize, in_item: bool) {
+    for (i, (key, value)) in fields.iter().enumerate() {
+        if i > 0 || !in_item {
+            out.push_str(&" ".repeat(indent));
+        }
+        match value {
+            Value::Object(fields) if !fields.is_empty() => {
+                out.push_str(&format!("{}:\n", key));
+                write_yaml_fields(out, fields, indent + 2, false);
+            }
+            Value::List(items) if !items.is_empty() => {
+                out.push_str(&format!("{}:\n", key));
+                write_yaml_items(out, items, indent + 2);
+            }
+            value => out.push_str(&format!("{}: {}\n", key, value.yaml_scalar())),
+        }
+    }
+}
+
+fn write_yaml_items(out: &mut String, items: &[Value], indent: usize) {
+    for item in items {
+        out.push_str(&format!("{}- ", " ".repeat(indent)));
+        match item {
+            Value::Object(fields) if !fields.is_empty() => {
+                write_yaml_fields(out, fields, indent + 2, true)
+            }
+            Value::List(items) if !items.is_empty() => {
+                out.push('\n');
+                write_yaml_items(out, items, indent + 2);
+            }
+            item => out.push_str(&format!("{}\n", item.yaml_scalar())),
+        }
+    }
+}
+
+// reads back what to_json writes (used for the cache), None on anything
+// malformed; numbers with a sign, fraction or exponent become floats
+pub fn parse_json(text: &str) -> Option<Value> {
+    let mut parser = Parser {
+        chars: text.chars().peekable(),
+    };
+    let value = parser.value()?;
+    parser.skip_whitespace();
+    parser.chars.peek().is_none().then_some(value)
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn expect(&mut self, word: &str) -> Option<()> {
+        word.chars()
+            .all(|c| self.chars.next_if_eq(&c).is_some())
+            .then_some(())
+    }
+
+    fn value(&mut self) -> Option<Value> {
+        self.skip_whitespace();
+        match *self.chars.peek()? {
+            'n' => self.expect("null").map(|_| Value::Null),
+            '"' => self.string().map(Value::Str),
+            '[' => {
+                self.chars.next();
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&']').is_some() {
+                    return Some(Value::List(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.next()? {
+                        ',' => continue,
+                        ']' => return Some(Value::List(items)),
+                        _ => return None,
+                    }
+                }
+            }
+            '{' => {
+                self.chars.next();
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.chars.next_if_eq(&'}').is_some() {
+                    return Some(Value::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.skip_whitespace();
+                    self.expect(":")?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.chars.next()? {
+                        ',' => continue,
+                        '}' => return Some(Value::Object(fields)),
+                        _ => return None,
+                    }
+                }
+            }
+            _ => self.number(),
+        }
+    }
+
+    fn string(&mut self) -> Option<String> {
+        self.expect("\"")?;
+        let mut out = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(out),
+                '\\' => match self.chars.next()? {
+                    'n' => out.push('\n'),
+                    'r' => out.push('\r'),
+                    't' => out.push('\t'),
+                    'u' => {
+                        let hex: String = (0..4).filter_map(|_| self.chars.next()).collect();
+                        out.push(char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?);
+                    }
+                    c => out.push(c),
+                },
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<Value> {
+        let mut text = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            text.push(c);
+        }
+        match text.parse::<u64>() {
+            Ok(n) => Some(Value::Number(n)),
+            Err(_) => text.parse::<f64>().ok().map(Value::Float),
+        }
+    }
+}
+
+impl Value {
+    // the field called key, for objects
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self {
+            Value::Number(n) => Some(*n),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Value]> {
+        match self {
+            Value::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
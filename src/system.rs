@@ -0,0 +1,2389 @@
+use libc::{
+    AF_INET, AF_INET6, USER_PROCESS, c_char, endutxent, freeifaddrs, gethostname, getifaddrs,
+    getutxent, ifaddrs, setutxent, sockaddr_in, sockaddr_in6, statvfs, sysinfo, ttyname, utsname,
+};
+
+use raw_cpuid::CpuId;
+
+use std::{
+    env,
+    ffi::{CStr, CString},
+    fs,
+    io::{Read, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, TcpStream, ToSocketAddrs},
+    os::unix::fs::MetadataExt,
+    path::PathBuf,
+    process::{Command, Stdio},
+    ptr,
+    sync::mpsc,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    Battery, Cpu, CpuUsage, Desktop, Disk, DiskTemp, Display, Dns, Gpu, InstalledRam, Kernel,
+    LocalIp, NetSpeed, RamHardware, Usage, Wifi, Zram, count_dirs, read_meminfo_fields,
+};
+
+// kernels before 3.14 have no MemAvailable, the old free+buffers+cached
+// estimate stands in for it there
+pub fn memory_usage() -> Option<Usage> {
+    let values = read_meminfo_fields(&[
+        "MemTotal:",
+        "MemAvailable:",
+        "MemFree:",
+        "Buffers:",
+        "Cached:",
+    ]);
+    let total = values[0]?;
+    let available = match values[1] {
+        Some(available) => available,
+        None => values[2]? + values[3].unwrap_or(0) + values[4].unwrap_or(0),
+    };
+
+    let used = total.saturating_sub(available);
+    Some(Usage {
+        used: used * 1024,
+        total: total * 1024,
+        avail: None,
+    })
+}
+
+pub fn swap_usage() -> Option<Usage> {
+    let values = read_meminfo_fields(&["SwapTotal:", "SwapFree:"]);
+    let (Some(total), Some(free)) = (values[0], values[1]) else {
+        return None;
+    };
+
+    let used = total.saturating_sub(free);
+    Some(Usage {
+        used: used * 1024,
+        total: total * 1024,
+        avail: None,
+    })
+}
+
+// zram swap devices from /proc/swaps, with the first two columns of their
+// mm_stat (orig_data_size and compr_data_size, in bytes)
+pub fn get_zram() -> Option<Zram> {
+    let swaps = fs::read_to_string("/proc/swaps").ok()?;
+    let stats: Vec<(u64, u64)> = swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().next()?.strip_prefix("/dev/"))
+        .filter(|device| device.starts_with("zram"))
+        .filter_map(|device| {
+            let stat = fs::read_to_string(format!("/sys/block/{}/mm_stat", device)).ok()?;
+            let mut values = stat.split_whitespace().map(|v| v.parse::<u64>().ok());
+            Some((values.next()??, values.next()??))
+        })
+        .collect();
+    if stats.is_empty() {
+        return None;
+    }
+    Some(Zram {
+        original: stats.iter().map(|s| s.0).sum(),
+        compressed: stats.iter().map(|s| s.1).sum(),
+        swappiness: fs::read_to_string("/proc/sys/vm/swappiness")
+            .ok()
+            .and_then(|s| s.trim().parse().ok()),
+    })
+}
+
+pub fn statvfs_usage(path: &str) -> Option<Usage> {
+    let c_path = CString::new(path).ok()?;
+    let mut stat: statvfs = unsafe { std::mem::zeroed() };
+
+    let ret = unsafe { statvfs(c_path.as_ptr() as *const c_char, &mut stat) };
+    if ret != 0 {
+        return None;
+    }
+
+    let (used, avail, total) = usage_from(
+        stat.f_blocks as u64,
+        stat.f_bfree as u64,
+        stat.f_bavail as u64,
+        stat.f_frsize as u64,
+    );
+    Some(Usage {
+        used,
+        total,
+        avail: Some(avail),
+    })
+}
+
+// (used, avail, total) bytes from statvfs block counts, the way df counts
+// them: used is everything not free, f_bavail is what non-root users can
+// still write, and the root reserve in between is neither. saturating, an
+// overflow (exabyte arrays) should read as huge, not wrap around to
+// something small, and counts that don't add up are clamped
+fn usage_from(blocks: u64, bfree: u64, bavail: u64, frsize: u64) -> (u64, u64, u64) {
+    let free = bfree.min(blocks);
+    let avail = bavail.min(free);
+    (
+        (blocks - free).saturating_mul(frsize),
+        avail.saturating_mul(frsize),
+        blocks.saturating_mul(frsize),
+    )
+}
+
+pub fn get_root_disk_usage() -> Option<Usage> {
+    statvfs_usage("/")
+}
+
+// / alone, plus the windows C: drive under WSL since / is only the distro's
+// virtual disk there
+pub fn get_root_disks() -> Vec<Disk> {
+    let mut mounts = vec!["/"];
+    if get_wsl().is_some() {
+        mounts.push("/mnt/c");
+    }
+    mounts
+        .into_iter()
+        .filter_map(|mount| {
+            Some(Disk {
+                mount: mount.to_string(),
+                usage: statvfs_usage(mount)?,
+            })
+        })
+        .collect()
+}
+
+// filesystems that don't live on a disk, or (overlay, aufs, shiftfs, lxcfs)
+// only re-expose one that is already listed, as on container hosts; / is
+// kept whatever it is, since inside a container it's usually an overlay
+static PSEUDO_FILESYSTEMS: [&str; 30] = [
+    "proc",
+    "sysfs",
+    "devtmpfs",
+    "devpts",
+    "tmpfs",
+    "ramfs",
+    "cgroup",
+    "cgroup2",
+    "securityfs",
+    "pstore",
+    "efivarfs",
+    "bpf",
+    "debugfs",
+    "tracefs",
+    "configfs",
+    "fusectl",
+    "mqueue",
+    "hugetlbfs",
+    "autofs",
+    "binfmt_misc",
+    "rpc_pipefs",
+    "nsfs",
+    "squashfs",
+    "overlay",
+    "aufs",
+    "shiftfs",
+    "selinuxfs",
+    "fuse.lxcfs",
+    "fuse.portal",
+    "fuse.gvfsd-fuse",
+];
+
+fn is_disk_mount(mount: &str, fstype: &str) -> bool {
+    mount == "/" || !PSEUDO_FILESYSTEMS.contains(&fstype)
+}
+
+// /proc/mounts escapes spaces and friends as \ooo octal
+fn unescape_mount_path(path: &str) -> String {
+    let bytes = path.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let octal = bytes
+            .get(i + 1..i + 4)
+            .filter(|digits| bytes[i] == b'\\' && digits.iter().all(|d| (b'0'..=b'7').contains(d)));
+        if let Some(digits) = octal {
+            let value = digits
+                .iter()
+                .fold(0u32, |acc, d| acc * 8 + (d - b'0') as u32);
+            out.push(value as u8);
+            i += 4;
+            continue;
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+// every real mountpoint; bind mounts of the same device are only listed once,
+// matched by the device column and by st_dev (which also catches a device
+// mounted under two names, like /dev/root and /dev/sda1)
+pub fn get_all_disks() -> Vec<Disk> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+
+    let mut seen_devices: Vec<&str> = Vec::new();
+    let mut seen_dev_ids: Vec<u64> = Vec::new();
+    let mut disks = Vec::new();
+    for line in mounts.lines() {
+        let mut fields = line.split_whitespace();
+        let (Some(device), Some(mount), Some(fstype)) =
+            (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let mount = unescape_mount_path(mount);
+        if !is_disk_mount(&mount, fstype) || seen_devices.contains(&device) {
+            continue;
+        }
+        let dev_id = fs::metadata(&mount).ok().map(|meta| meta.dev());
+        if dev_id.is_some_and(|id| seen_dev_ids.contains(&id)) {
+            continue;
+        }
+        let Some(usage) = statvfs_usage(&mount) else {
+            continue;
+        };
+        if usage.total == 0 {
+            continue;
+        }
+        seen_devices.push(device);
+        seen_dev_ids.extend(dev_id);
+        disks.push(Disk { mount, usage });
+    }
+    disks
+}
+
+pub struct OsRelease {
+    pub name: String,
+    pub id: String,
+    pub id_like: String,
+    pub ansi_color: Option<String>,
+}
+
+// os-release values follow shell quoting: double quotes allow \" \\ \$ and
+// \` escapes, single quotes are literal, bare values may escape any char
+fn unquote_os_release_value(raw: &str) -> String {
+    let raw = raw.trim();
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars();
+    let mut quote = None;
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (Some('\''), _) => out.push(c),
+            (Some(_), '\\') => match chars.next() {
+                Some(next @ ('"' | '\\' | '$' | '`')) => out.push(next),
+                Some(next) => {
+                    out.push('\\');
+                    out.push(next);
+                }
+                None => out.push('\\'),
+            },
+            (None, '\\') => out.extend(chars.next()),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+pub fn parse_os_release(text: &str) -> OsRelease {
+    let mut os = OsRelease {
+        name: "Unknown OS".to_string(),
+        id: String::new(),
+        id_like: String::new(),
+        ansi_color: None,
+    };
+    let mut pretty_name = None;
+    let mut name = None;
+    let mut version = None;
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = unquote_os_release_value(value);
+        match key.trim() {
+            "PRETTY_NAME" => pretty_name = Some(value),
+            "NAME" => name = Some(value),
+            "VERSION" => version = Some(value),
+            "ID" => os.id = value,
+            "ID_LIKE" => os.id_like = value,
+            "ANSI_COLOR" => os.ansi_color = Some(value),
+            _ => {}
+        }
+    }
+
+    // minimal distros may only set NAME and VERSION
+    let composed = match (name, version) {
+        (Some(name), Some(version)) => Some(format!("{} {}", name, version)),
+        (name, _) => name,
+    };
+    if let Some(name) = pretty_name
+        .filter(|n| !n.is_empty())
+        .or(composed)
+        .filter(|n| !n.is_empty())
+    {
+        os.name = name;
+    }
+    os
+}
+
+pub fn read_os_release(path: &str) -> OsRelease {
+    parse_os_release(&fs::read_to_string(path).unwrap_or_default())
+}
+
+// /etc/os-release takes precedence over the vendor copy in /usr/lib
+pub fn os_release() -> OsRelease {
+    if std::path::Path::new("/etc/os-release").exists() {
+        read_os_release("/etc/os-release")
+    } else {
+        read_os_release("/usr/lib/os-release")
+    }
+}
+
+// values firmware vendors leave in unfilled DMI fields
+static DMI_PLACEHOLDERS: [&str; 14] = [
+    "to be filled by o.e.m.",
+    "default string",
+    "system product name",
+    "system manufacturer",
+    "system version",
+    "not applicable",
+    "not specified",
+    "none",
+    "o.e.m.",
+    "oem",
+    "invalid",
+    "all series",
+    "type1productconfigid",
+    "x.x",
+];
+
+fn read_dmi_field(name: &str) -> Option<String> {
+    let value = fs::read_to_string(format!("/sys/class/dmi/id/{}", name)).ok()?;
+    let value = value.trim();
+    (!value.is_empty() && !DMI_PLACEHOLDERS.contains(&value.to_lowercase().as_str()))
+        .then(|| value.to_string())
+}
+
+// e.g. "LENOVO ThinkPad X1 Carbon Gen 9" from sys_vendor, product_name and
+// product_version; None when the firmware doesn't say (or there's no DMI)
+pub fn get_host_model() -> Option<String> {
+    let vendor = read_dmi_field("sys_vendor");
+    let product = read_dmi_field("product_name");
+    let version = read_dmi_field("product_version");
+
+    // lenovo's product_name is a part number, the marketing name is the version
+    let is_lenovo = vendor
+        .as_deref()
+        .is_some_and(|v| v.eq_ignore_ascii_case("lenovo"));
+    let parts: Vec<String> = if is_lenovo && version.is_some() {
+        vendor.into_iter().chain(version).collect()
+    } else {
+        vendor.into_iter().chain(product).chain(version).collect()
+    };
+
+    // vendors often repeat themselves, e.g. "Dell Inc." + "Dell XPS 13"
+    let mut model: Vec<&str> = Vec::new();
+    for word in parts.iter().flat_map(|p| p.split_whitespace()) {
+        if !model.iter().any(|w| w.eq_ignore_ascii_case(word)) {
+            model.push(word);
+        }
+    }
+    (!model.is_empty()).then(|| model.join(" "))
+}
+
+// e.g. "ASUS PRIME B550-PLUS"
+pub fn get_board() -> Option<String> {
+    let parts: Vec<String> = [read_dmi_field("board_vendor"), read_dmi_field("board_name")]
+        .into_iter()
+        .flatten()
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" "))
+}
+
+// e.g. "American Megatrends 2803 (2023-05-01)", bios_date is MM/DD/YYYY
+pub fn get_bios() -> Option<String> {
+    let parts: Vec<String> = [
+        read_dmi_field("bios_vendor"),
+        read_dmi_field("bios_version"),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    if parts.is_empty() {
+        return None;
+    }
+    let date =
+        read_dmi_field("bios_date").map(|date| match date.split('/').collect::<Vec<_>>()[..] {
+            [month, day, year] => format!("{}-{}-{}", year, month, day),
+            _ => date,
+        });
+    Some(match date {
+        Some(date) => format!("{} ({})", parts.join(" "), date),
+        None => parts.join(" "),
+    })
+}
+
+pub fn get_username() -> String {
+    env::var("USER").unwrap_or_else(|_| "unknown".into())
+}
+
+// short hostname, without the domain part
+pub fn get_hostname() -> String {
+    unsafe {
+        let mut buf = [0u8; 256];
+        if gethostname(buf.as_mut_ptr() as *mut i8, buf.len()) == 0 {
+            CStr::from_ptr(buf.as_ptr() as *const i8)
+                .to_string_lossy()
+                .split('.')
+                .next()
+                .unwrap_or("unknown")
+                .to_string()
+        } else {
+            "unknown".into()
+        }
+    }
+}
+
+// WSL kernels carry "microsoft" in their version string, WSL2 ones are
+// named like 5.15.x-microsoft-standard-WSL2
+pub fn parse_wsl_version(proc_version: &str) -> Option<u8> {
+    let version = proc_version.to_lowercase();
+    if !version.contains("microsoft") && !version.contains("wsl") {
+        return None;
+    }
+    if version.contains("wsl2") || version.contains("microsoft-standard") {
+        Some(2)
+    } else {
+        Some(1)
+    }
+}
+
+pub fn get_wsl() -> Option<u8> {
+    parse_wsl_version(&fs::read_to_string("/proc/version").ok()?)
+}
+
+// (substring of sys_vendor/product_name, hypervisor name), checked in order.
+// Microsoft and Amazon also make the hardware of surfaces and .metal
+// instances, see hypervisor_from_dmi
+static HYPERVISORS: [(&str, &str); 12] = [
+    ("KVM", "KVM"),
+    ("QEMU", "QEMU"),
+    ("VMware", "VMware"),
+    ("VirtualBox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Microsoft Corporation", "Hyper-V"),
+    ("Parallels", "Parallels"),
+    ("Bochs", "Bochs"),
+    ("BHYVE", "bhyve"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("Google Compute Engine", "Google Compute Engine"),
+];
+
+// the same markers systemd-detect-virt looks at, most specific first
+fn detect_container() -> Option<String> {
+    if fs::metadata("/.dockerenv").is_ok() {
+        return Some("Docker".to_string());
+    }
+    if fs::metadata("/run/.containerenv").is_ok() {
+        return Some("Podman".to_string());
+    }
+    // set by lxc, systemd-nspawn and friends, usually only readable as root
+    if let Ok(environ) = fs::read("/proc/1/environ") {
+        let container = environ
+            .split(|&b| b == 0)
+            .find_map(|var| var.strip_prefix(b"container="));
+        if let Some(name) = container {
+            return Some(String::from_utf8_lossy(name).into_owned());
+        }
+    }
+    let cgroup = fs::read_to_string("/proc/1/cgroup").unwrap_or_default();
+    [
+        ("kubepods", "Kubernetes"),
+        ("docker", "Docker"),
+        ("lxc", "LXC"),
+    ]
+    .iter()
+    .find(|(marker, _)| cgroup.contains(marker))
+    .map(|(_, name)| name.to_string())
+}
+
+fn detect_hypervisor() -> Option<&'static str> {
+    let read = |f: &str| fs::read_to_string(format!("/sys/class/dmi/id/{}", f)).unwrap_or_default();
+    hypervisor_from_dmi(
+        read("sys_vendor").trim(),
+        read("product_name").trim(),
+        read("board_vendor").trim(),
+        read_hypervisor_flag(),
+    )
+}
+
+// like systemd-detect-virt, a vendor only counts when the cpu says it's
+// virtualized; arm has no flags line to ask, so there the dmi has to do.
+// a Microsoft vendor is only Hyper-V with its "Virtual Machine" product
+fn hypervisor_from_dmi(
+    vendor: &str,
+    product: &str,
+    board_vendor: &str,
+    hypervisor_flag: Option<bool>,
+) -> Option<&'static str> {
+    if hypervisor_flag == Some(false) {
+        return None;
+    }
+    let dmi = format!("{}{}{}", vendor, product, board_vendor);
+    HYPERVISORS
+        .iter()
+        .find(|(needle, _)| dmi.contains(needle))
+        .map(|(_, name)| *name)
+        .filter(|&name| name != "Hyper-V" || product == "Virtual Machine")
+}
+
+// set inside any vm, even when dmi is missing or the vendor is unknown.
+// None when cpuinfo has no flags to look at (arm)
+fn read_hypervisor_flag() -> Option<bool> {
+    let cpuinfo = fs::read_to_string("/proc/cpuinfo").ok()?;
+    let mut flags = cpuinfo
+        .lines()
+        .filter(|line| line.starts_with("flags"))
+        .peekable();
+    flags.peek()?;
+    Some(flags.any(|line| line.split_whitespace().any(|flag| flag == "hypervisor")))
+}
+
+// e.g. "Docker container", "KVM Virtual Machine", "WSL2" or "Bare metal"
+pub fn get_host_type() -> String {
+    // WSL2 is a Hyper-V vm, but that's not what anyone wants to see
+    if let Some(version) = get_wsl() {
+        format!("WSL{}", version)
+    } else if let Some(container) = detect_container() {
+        format!("{} container", container)
+    } else if let Some(hypervisor) = detect_hypervisor() {
+        format!("{} Virtual Machine", hypervisor)
+    } else if read_hypervisor_flag() == Some(true) {
+        "Virtual Machine".to_string()
+    } else {
+        "Bare metal".to_string()
+    }
+}
+
+pub fn get_cpu() -> String {
+    CpuId::new()
+        .get_processor_brand_string()
+        .map(|b| b.as_str().trim().to_string())
+        .unwrap_or_else(|| "Unknown CPU".to_string())
+}
+
+// highest cpuinfo_max_freq across all cpus so big.LITTLE reports the fast cluster
+fn read_cpufreq_max_mhz() -> Option<u64> {
+    fs::read_dir("/sys/devices/system/cpu")
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            let path = e.path().join("cpufreq/cpuinfo_max_freq");
+            let khz: u64 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+            Some(khz / 1000)
+        })
+        .max()
+}
+
+pub fn get_cpu_details() -> Cpu {
+    let mut cpu = Cpu {
+        model: get_cpu(),
+        cores: None,
+        threads: None,
+        sockets: None,
+        max_freq_mhz: read_cpufreq_max_mhz(),
+    };
+
+    let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") else {
+        return cpu;
+    };
+
+    let mut threads = 0;
+    let mut cores: Vec<(String, String)> = Vec::new();
+    let mut physical_id = String::new();
+    let mut sockets: Vec<String> = Vec::new();
+    let mut cpuinfo_mhz: f64 = 0.0;
+    for line in cpuinfo.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "processor" => threads += 1,
+            "physical id" => {
+                physical_id = value.to_string();
+                if !sockets.contains(&physical_id) {
+                    sockets.push(physical_id.clone());
+                }
+            }
+            "core id" => {
+                let core = (physical_id.clone(), value.to_string());
+                if !cores.contains(&core) {
+                    cores.push(core);
+                }
+            }
+            "cpu MHz" => {
+                if let Ok(mhz) = value.parse::<f64>() {
+                    cpuinfo_mhz = cpuinfo_mhz.max(mhz);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if threads > 0 {
+        cpu.threads = Some(threads);
+        // arm kernels don't list core ids, so assume one thread per core
+        cpu.cores = Some(if cores.is_empty() {
+            threads
+        } else {
+            cores.len()
+        });
+    }
+    if !sockets.is_empty() {
+        cpu.sockets = Some(sockets.len());
+    }
+    if cpu.max_freq_mhz.is_none() && cpuinfo_mhz > 0.0 {
+        cpu.max_freq_mhz = Some(cpuinfo_mhz.round() as u64);
+    }
+    cpu
+}
+
+pub const DEFAULT_CPU_SAMPLE_MS: u64 = 100;
+
+// (busy, total) jiffies of the "cpu" line followed by each "cpuN" line;
+// idle and iowait count as not busy, guest time is already part of user
+pub fn parse_proc_stat(stat: &str) -> Vec<(u64, u64)> {
+    stat.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .map(|line| {
+            let times: Vec<u64> = line
+                .split_whitespace()
+                .skip(1)
+                .take(8)
+                .filter_map(|v| v.parse().ok())
+                .collect();
+            let total: u64 = times.iter().sum();
+            let idle = times.get(3).unwrap_or(&0) + times.get(4).unwrap_or(&0);
+            (total.saturating_sub(idle), total)
+        })
+        .collect()
+}
+
+// percent busy per line between two parse_proc_stat snapshots
+pub fn cpu_usage_between(before: &[(u64, u64)], after: &[(u64, u64)]) -> Vec<u64> {
+    before
+        .iter()
+        .zip(after)
+        .map(|(&(busy1, total1), &(busy2, total2))| {
+            let total = total2.saturating_sub(total1);
+            if total == 0 {
+                return 0;
+            }
+            (busy2.saturating_sub(busy1) * 100 / total).min(100)
+        })
+        .collect()
+}
+
+// blocks for interval between the two samples
+pub fn get_cpu_usage(interval: Duration) -> Option<CpuUsage> {
+    let before = parse_proc_stat(&fs::read_to_string("/proc/stat").ok()?);
+    thread::sleep(interval);
+    let after = parse_proc_stat(&fs::read_to_string("/proc/stat").ok()?);
+    let usage = cpu_usage_between(&before, &after);
+    let (&total, cores) = usage.split_first()?;
+    Some(CpuUsage {
+        total,
+        cores: cores.to_vec(),
+    })
+}
+
+// pci.ids locations used by lspci on the common distros
+static PCI_IDS_PATHS: [&str; 3] = [
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+];
+
+fn short_vendor_name(vendor_id: &str) -> Option<&'static str> {
+    match vendor_id {
+        "8086" => Some("Intel"),
+        "1002" => Some("AMD"),
+        "10de" => Some("NVIDIA"),
+        _ => None,
+    }
+}
+
+// resolve (vendor, device) id pairs to names in a single pass over pci.ids
+fn resolve_pci_names(ids: &[(String, String)]) -> Vec<String> {
+    let mut names: Vec<Option<String>> = vec![None; ids.len()];
+    let mut vendor_names: Vec<Option<String>> = vec![None; ids.len()];
+
+    if let Some(db) = PCI_IDS_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+    {
+        let mut current_vendor = String::new();
+        for line in db.lines() {
+            if line.starts_with('#') || line.is_empty() || line.starts_with("\t\t") {
+                continue;
+            }
+            if line.starts_with("C ") {
+                break; // device classes follow the vendor list
+            }
+            if let Some(dev_line) = line.strip_prefix('\t') {
+                let Some((dev_id, dev_name)) = dev_line.split_once("  ") else {
+                    continue;
+                };
+                for (i, (vendor, device)) in ids.iter().enumerate() {
+                    if *vendor == current_vendor && device == dev_id {
+                        names[i] = Some(dev_name.trim().to_string());
+                    }
+                }
+            } else {
+                let Some((vendor_id, vendor_name)) = line.split_once("  ") else {
+                    continue;
+                };
+                current_vendor = vendor_id.to_string();
+                for (i, (vendor, _)) in ids.iter().enumerate() {
+                    if *vendor == current_vendor {
+                        vendor_names[i] = Some(vendor_name.trim().to_string());
+                    }
+                }
+            }
+        }
+    }
+
+    ids.iter()
+        .enumerate()
+        .map(|(i, (vendor, device))| {
+            let vendor_name = short_vendor_name(vendor)
+                .map(|v| v.to_string())
+                .or_else(|| vendor_names[i].clone())
+                .unwrap_or_else(|| vendor.clone());
+            match &names[i] {
+                Some(name) => format!("{} {}", vendor_name, name),
+                None => format!("{} [{}:{}]", vendor_name, vendor, device),
+            }
+        })
+        .collect()
+}
+
+// pci vendor/device ids and the sysfs device dir of each drm card
+fn read_drm_gpu_ids() -> Vec<(String, String, String)> {
+    let mut cards: Vec<_> = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| {
+                // skip connectors like card0-HDMI-A-1 and render nodes
+                name.len() > 4
+                    && name.starts_with("card")
+                    && name[4..].chars().all(|c| c.is_ascii_digit())
+            })
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    cards.sort();
+
+    let mut ids: Vec<(String, String, String)> = Vec::new();
+    for card in cards {
+        let dev = format!("/sys/class/drm/{}/device", card);
+        let read_id = |file: &str| {
+            fs::read_to_string(format!("{}/{}", dev, file))
+                .unwrap_or_default()
+                .trim()
+                .trim_start_matches("0x")
+                .to_lowercase()
+        };
+        let (vendor, device) = (read_id("vendor"), read_id("device"));
+        if vendor.is_empty() || device.is_empty() {
+            continue;
+        }
+        // dedupe by the device the card links to rather than its ids, two
+        // of the same gpu are still two gpus
+        let dev = fs::canonicalize(&dev)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or(dev);
+        if !ids.iter().any(|(_, _, d)| *d == dev) {
+            ids.push((vendor, device, dev));
+        }
+    }
+    ids
+}
+
+// name of the bound kernel module, e.g. amdgpu, i915 or nvidia
+fn read_gpu_driver(device_dir: &str) -> Option<String> {
+    let link = fs::read_link(format!("{}/driver", device_dir)).ok()?;
+    Some(link.file_name()?.to_string_lossy().to_string())
+}
+
+// amdgpu exposes this, i915 (shared memory) and nvidia don't
+fn read_gpu_vram(device_dir: &str) -> Option<u64> {
+    let bytes: u64 = fs::read_to_string(format!("{}/mem_info_vram_total", device_dir))
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    (bytes > 0).then_some(bytes)
+}
+
+// total memory of each nvidia gpu in bytes, in nvidia-smi's (pci bus) order
+fn read_nvidia_smi_vram() -> Vec<u64> {
+    let output = match Command::new("nvidia-smi")
+        .args(["--query-gpu=memory.total", "--format=csv,noheader,nounits"])
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(out) if out.status.success() => out.stdout,
+        _ => return Vec::new(),
+    };
+    String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|mib| mib.trim().parse::<u64>().ok())
+        .map(|mib| mib * 1024 * 1024)
+        .collect()
+}
+
+fn read_lspci_gpus() -> Vec<String> {
+    let output = match Command::new("lspci").arg("-mm").output() {
+        Ok(out) if out.status.success() => out.stdout,
+        _ => return Vec::new(),
+    };
+
+    // lines look like: 00:02.0 "VGA compatible controller" "Intel Corporation" "UHD Graphics 620" ...
+    String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+            let class = fields.first()?;
+            if !(class.contains("VGA") || class.contains("3D") || class.contains("Display")) {
+                return None;
+            }
+            Some(format!("{} {}", fields.get(1)?, fields.get(2)?))
+        })
+        .collect()
+}
+
+pub fn get_gpu() -> Vec<Gpu> {
+    let cards = read_drm_gpu_ids();
+    if cards.is_empty() {
+        return read_lspci_gpus()
+            .into_iter()
+            .map(|name| Gpu {
+                name,
+                driver: None,
+                vram: None,
+            })
+            .collect();
+    }
+
+    let ids: Vec<(String, String)> = cards
+        .iter()
+        .map(|(vendor, device, _)| (vendor.clone(), device.clone()))
+        .collect();
+    let mut gpus: Vec<Gpu> = resolve_pci_names(&ids)
+        .into_iter()
+        .zip(&cards)
+        .map(|(name, (_, _, dir))| Gpu {
+            name,
+            driver: read_gpu_driver(dir),
+            vram: read_gpu_vram(dir),
+        })
+        .collect();
+
+    // the proprietary driver has no sysfs vram, only ask nvidia-smi if needed
+    let nvidia: Vec<&mut Gpu> = gpus
+        .iter_mut()
+        .filter(|gpu| gpu.driver.as_deref() == Some("nvidia") && gpu.vram.is_none())
+        .collect();
+    if !nvidia.is_empty() {
+        for (gpu, vram) in nvidia.into_iter().zip(read_nvidia_smi_vram()) {
+            gpu.vram = Some(vram);
+        }
+    }
+    gpus
+}
+
+// sensor names that report the cpu package rather than e.g. acpi or wifi
+static CPU_SENSOR_NAMES: [&str; 7] = [
+    "x86_pkg_temp",
+    "coretemp",
+    "k10temp",
+    "zenpower",
+    "cpu_thermal",
+    "cpu-thermal",
+    "soc_thermal",
+];
+
+fn read_millidegrees(path: &str) -> Option<f64> {
+    let milli: i64 = fs::read_to_string(path).ok()?.trim().parse().ok()?;
+    Some(milli as f64 / 1000.0)
+}
+
+// temperature in celsius from the cpu thermal zone, or a cpu hwmon sensor
+pub fn get_cpu_temp() -> Option<f64> {
+    let mut zones: Vec<String> = fs::read_dir("/sys/class/thermal")
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|name| name.starts_with("thermal_zone"))
+                .collect()
+        })
+        .unwrap_or_default();
+    zones.sort();
+    for zone in &zones {
+        let dir = format!("/sys/class/thermal/{}", zone);
+        let kind = fs::read_to_string(format!("{}/type", dir)).unwrap_or_default();
+        if CPU_SENSOR_NAMES.contains(&kind.trim())
+            && let Some(temp) = read_millidegrees(&format!("{}/temp", dir))
+        {
+            return Some(temp);
+        }
+    }
+
+    let mut hwmons: Vec<String> = fs::read_dir("/sys/class/hwmon")
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    hwmons.sort();
+    hwmons.iter().find_map(|hwmon| {
+        let dir = format!("/sys/class/hwmon/{}", hwmon);
+        let name = fs::read_to_string(format!("{}/name", dir)).ok()?;
+        if !CPU_SENSOR_NAMES.contains(&name.trim()) {
+            return None;
+        }
+        read_millidegrees(&format!("{}/temp1_input", dir))
+    })
+}
+
+// temp1_input of the first hwmon under a sysfs device dir
+fn device_hwmon_temp(device_dir: &str) -> Option<f64> {
+    let mut hwmons: Vec<String> = fs::read_dir(format!("{}/hwmon", device_dir))
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    hwmons.sort();
+    hwmons
+        .iter()
+        .find_map(|hwmon| read_millidegrees(&format!("{}/hwmon/{}/temp1_input", device_dir, hwmon)))
+}
+
+fn read_nvidia_smi_temps() -> Vec<f64> {
+    let output = match Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=temperature.gpu",
+            "--format=csv,noheader,nounits",
+        ])
+        .stderr(Stdio::null())
+        .output()
+    {
+        Ok(out) if out.status.success() => out.stdout,
+        _ => return Vec::new(),
+    };
+    String::from_utf8_lossy(&output)
+        .lines()
+        .filter_map(|temp| temp.trim().parse::<f64>().ok())
+        .collect()
+}
+
+// the hwmon sensor of each drm card (amdgpu, nouveau, ...), plus nvidia-smi
+// for the proprietary driver, which has none
+pub fn get_gpu_temps() -> Vec<f64> {
+    let cards = read_drm_gpu_ids();
+    let mut temps: Vec<f64> = cards
+        .iter()
+        .filter_map(|(_, _, dir)| device_hwmon_temp(dir))
+        .collect();
+    let nvidia = cards
+        .iter()
+        .any(|(_, _, dir)| read_gpu_driver(dir).as_deref() == Some("nvidia"));
+    if nvidia {
+        temps.extend(read_nvidia_smi_temps());
+    }
+    temps
+}
+
+// drives with a temperature sensor: the nvme hwmon sits on the controller
+// (nvme0), drivetemp's on the scsi device with the block device under it
+pub fn get_disk_temps() -> Vec<DiskTemp> {
+    let Ok(entries) = fs::read_dir("/sys/class/hwmon") else {
+        return Vec::new();
+    };
+    let mut temps: Vec<DiskTemp> = entries
+        .flatten()
+        .filter_map(|e| {
+            let dir = e.path();
+            let name = fs::read_to_string(dir.join("name")).ok()?;
+            let device = fs::canonicalize(dir.join("device")).ok()?;
+            let device = match name.trim() {
+                "nvme" => device.file_name()?.to_string_lossy().to_string(),
+                "drivetemp" => fs::read_dir(device.join("block"))
+                    .ok()?
+                    .flatten()
+                    .next()?
+                    .file_name()
+                    .to_string_lossy()
+                    .to_string(),
+                _ => return None,
+            };
+            let celsius = read_millidegrees(&dir.join("temp1_input").to_string_lossy())?;
+            Some(DiskTemp { device, celsius })
+        })
+        .collect();
+    temps.sort_by(|a, b| a.device.cmp(&b.device));
+    temps
+}
+
+// SMBIOS memory type byte (type 17, offset 0x12) to its name
+fn smbios_memory_type(code: u8) -> Option<&'static str> {
+    Some(match code {
+        0x0F => "SDRAM",
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x14 => "DDR2 FB-DIMM",
+        0x18 => "DDR3",
+        0x1A => "DDR4",
+        0x1B => "LPDDR",
+        0x1C => "LPDDR2",
+        0x1D => "LPDDR3",
+        0x1E => "LPDDR4",
+        0x20 => "HBM",
+        0x21 => "HBM2",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        _ => return None,
+    })
+}
+
+// walks the raw structure table from /sys/firmware/dmi/tables/DMI: each
+// structure is a type/length/handle header and its formatted fields,
+// followed by a string set ending in two nul bytes
+pub fn parse_smbios_memory(table: &[u8]) -> Option<RamHardware> {
+    let byte = |s: &[u8], off: usize| s.get(off).copied();
+    let word = |s: &[u8], off: usize| Some(u16::from_le_bytes([byte(s, off)?, byte(s, off + 1)?]));
+
+    let mut slots = 0;
+    let mut size: Option<u64> = None;
+    let mut modules: Vec<(&'static str, Option<u32>)> = Vec::new();
+    let mut pos = 0;
+    while pos + 4 <= table.len() {
+        let (kind, len) = (table[pos], table[pos + 1] as usize);
+        if kind == 127 || len < 4 || pos + len > table.len() {
+            break;
+        }
+        let entry = &table[pos..pos + len];
+        if kind == 17 {
+            slots += 1;
+            // size 0 is an empty slot
+            let installed = word(entry, 0x0C).is_some_and(|size| size != 0);
+            if let Some(bytes) = smbios_module_size(entry) {
+                *size.get_or_insert(0) += bytes;
+            }
+            if installed && let Some(name) = byte(entry, 0x12).and_then(smbios_memory_type) {
+                // configured speed (smbios 2.7) is what it actually runs at
+                let speed = [word(entry, 0x20), word(entry, 0x15)]
+                    .into_iter()
+                    .flatten()
+                    .find(|&s| s != 0 && s != 0xFFFF)
+                    .map(u32::from);
+                modules.push((name, speed));
+            }
+        }
+        // skip the string set, which is at least the two terminating nuls
+        let strings = pos + len;
+        let end = table[strings..]
+            .windows(2)
+            .position(|w| w == [0, 0])
+            .map(|i| strings + i + 2)?;
+        pos = end;
+    }
+
+    let dominant = modules
+        .iter()
+        .max_by_key(|m| modules.iter().filter(|other| other == m).count())?;
+    Some(RamHardware {
+        kind: dominant.0.to_string(),
+        speed: dominant.1,
+        populated: modules.len(),
+        slots,
+        size,
+    })
+}
+
+// bytes from the size word at 0x0C: MiB, or KiB with bit 15 set, with
+// 0x7FFF meaning the real size is the MiB dword at 0x1C (32 GiB and up);
+// 0 is an empty slot and 0xFFFF unknown
+fn smbios_module_size(entry: &[u8]) -> Option<u64> {
+    let size = u16::from_le_bytes([*entry.get(0x0C)?, *entry.get(0x0D)?]) as u64;
+    match size {
+        0 | 0xFFFF => None,
+        0x7FFF => {
+            let extended = entry.get(0x1C..0x20)?;
+            let mib = u32::from_le_bytes(extended.try_into().ok()?) & 0x7FFF_FFFF;
+            Some(mib as u64 * 1024 * 1024)
+        }
+        size if size & 0x8000 != 0 => Some((size & 0x7FFF) * 1024),
+        size => Some(size * 1024 * 1024),
+    }
+}
+
+pub fn get_ram_hardware() -> Option<RamHardware> {
+    parse_smbios_memory(&fs::read("/sys/firmware/dmi/tables/DMI").ok()?)
+}
+
+// from what the ram_type and memory collectors already read
+pub fn get_ram_installed(ram: Option<&RamHardware>, memory: Option<Usage>) -> Option<InstalledRam> {
+    match ram.and_then(|ram| ram.size) {
+        Some(bytes) => Some(InstalledRam {
+            bytes,
+            from_smbios: true,
+        }),
+        None => Some(InstalledRam {
+            bytes: memory?.total,
+            from_smbios: false,
+        }),
+    }
+}
+
+pub fn get_battery() -> Vec<Battery> {
+    let Ok(entries) = fs::read_dir("/sys/class/power_supply") else {
+        return Vec::new();
+    };
+    let mut batteries: Vec<String> = entries
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|name| name.starts_with("BAT"))
+        .collect();
+    batteries.sort();
+
+    batteries
+        .iter()
+        .filter_map(|bat| {
+            let dir = format!("/sys/class/power_supply/{}", bat);
+            let capacity = fs::read_to_string(format!("{}/capacity", dir)).ok()?;
+            let status =
+                fs::read_to_string(format!("{}/status", dir)).unwrap_or_else(|_| "Unknown".into());
+            Some(Battery {
+                capacity: capacity.trim().parse().ok()?,
+                status: status.trim().to_string(),
+            })
+        })
+        .collect()
+}
+
+// whether any mains adapter (AC*, ADP*) is online; None without a battery, so
+// desktops (some of which list an adapter too) don't get the field
+pub fn get_ac_status() -> Option<bool> {
+    let entries: Vec<String> = fs::read_dir("/sys/class/power_supply")
+        .ok()?
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    if !entries.iter().any(|name| name.starts_with("BAT")) {
+        return None;
+    }
+    let online: Vec<bool> = entries
+        .iter()
+        .filter(|name| name.starts_with("AC") || name.starts_with("ADP"))
+        .filter_map(|name| {
+            fs::read_to_string(format!("/sys/class/power_supply/{}/online", name)).ok()
+        })
+        .map(|online| online.trim() == "1")
+        .collect();
+    (!online.is_empty()).then(|| online.contains(&true))
+}
+
+// percent of the backlight with the highest max_brightness, which is usually
+// the panel's native one rather than a coarse vendor or acpi interface
+pub fn get_brightness() -> Option<u64> {
+    let entries = fs::read_dir("/sys/class/backlight").ok()?;
+    let read =
+        |path: PathBuf| -> Option<u64> { fs::read_to_string(path).ok()?.trim().parse().ok() };
+    entries
+        .flatten()
+        .filter_map(|e| {
+            let max = read(e.path().join("max_brightness")).filter(|&max| max > 0)?;
+            Some((read(e.path().join("brightness"))?, max))
+        })
+        .max_by_key(|&(_, max)| max)
+        .map(|(current, max)| (current * 100 / max).min(100))
+}
+
+fn count_dpkg_packages() -> Option<usize> {
+    let status = fs::read_to_string("/var/lib/dpkg/status").ok()?;
+    Some(
+        status
+            .lines()
+            .filter(|line| *line == "Status: install ok installed")
+            .count(),
+    )
+}
+
+fn count_command_lines(cmd: &str, args: &[&str]) -> Option<usize> {
+    let output = Command::new(cmd).args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&output.stdout).lines().count())
+}
+
+fn count_flatpak_packages() -> Option<usize> {
+    let system = count_dirs("/var/lib/flatpak/app");
+    let user = env::var("HOME")
+        .ok()
+        .and_then(|home| count_dirs(&format!("{}/.local/share/flatpak/app", home)));
+    match (system, user) {
+        (None, None) => None,
+        (s, u) => Some(s.unwrap_or(0) + u.unwrap_or(0)),
+    }
+}
+
+fn count_nix_packages() -> Option<usize> {
+    if !std::path::Path::new("/nix/var/nix").exists() {
+        return None;
+    }
+    let profile = if std::path::Path::new("/run/current-system/sw").exists() {
+        "/run/current-system/sw".to_string()
+    } else {
+        format!("{}/.nix-profile", env::var("HOME").ok()?)
+    };
+    count_command_lines("nix-store", &["-q", "--requisites", &profile])
+}
+
+// every manager get_packages can report
+pub static PACKAGE_MANAGERS: [&str; 5] = ["pacman", "dpkg", "rpm", "flatpak", "nix"];
+
+pub fn get_packages() -> Vec<(&'static str, usize)> {
+    // status files and directories first, spawning only when there is no cheaper way
+    let counts = [
+        (count_dirs("/var/lib/pacman/local"), "pacman"),
+        (count_dpkg_packages(), "dpkg"),
+        (
+            if std::path::Path::new("/var/lib/rpm").exists() {
+                count_command_lines("rpm", &["-qa"])
+            } else {
+                None
+            },
+            "rpm",
+        ),
+        (count_flatpak_packages(), "flatpak"),
+        (count_nix_packages(), "nix"),
+    ];
+
+    counts
+        .into_iter()
+        .filter_map(|(count, manager)| match count {
+            Some(n) if n > 0 => Some((manager, n)),
+            _ => None,
+        })
+        .collect()
+}
+
+// parses `xrandr --current`: connected outputs look like
+//   HDMI-1 connected primary 1920x1080+0+0 ...
+// followed by their modes, the active rate marked with a *:
+//      1920x1080     60.00*+  59.94
+pub fn parse_xrandr(text: &str) -> Vec<Display> {
+    let mut displays = Vec::new();
+    let mut current: Option<Display> = None;
+    for line in text.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            displays.extend(current.take());
+            if line.contains(" connected") {
+                current = line
+                    .split_whitespace()
+                    .find(|token| token.contains('x') && token.contains('+'))
+                    .and_then(|geometry| geometry.split('+').next())
+                    .map(|mode| Display {
+                        mode: mode.to_string(),
+                        refresh: None,
+                    });
+            }
+            continue;
+        }
+        if let Some(display) = current.as_mut()
+            && display.refresh.is_none()
+        {
+            display.refresh = line
+                .split_whitespace()
+                .find(|token| token.contains('*'))
+                .and_then(|rate| rate.trim_end_matches(['*', '+']).parse::<f64>().ok())
+                .map(|rate| rate.round() as u32);
+        }
+    }
+    displays.extend(current);
+    displays
+}
+
+fn read_xrandr_displays() -> Vec<Display> {
+    if env::var("DISPLAY").is_err() {
+        return Vec::new();
+    }
+    match Command::new("xrandr").arg("--current").output() {
+        Ok(out) if out.status.success() => parse_xrandr(&String::from_utf8_lossy(&out.stdout)),
+        _ => Vec::new(),
+    }
+}
+
+// refresh rate of the preferred mode, from the first detailed timing
+// descriptor: pixel clock / (horizontal total * vertical total)
+pub fn parse_edid_refresh(edid: &[u8]) -> Option<u32> {
+    let dtd = edid.get(54..72)?;
+    let clock = u16::from_le_bytes([dtd[0], dtd[1]]) as u64 * 10_000;
+    let h_active = dtd[2] as u64 | ((dtd[4] as u64 >> 4) << 8);
+    let h_blank = dtd[3] as u64 | ((dtd[4] as u64 & 0x0f) << 8);
+    let v_active = dtd[5] as u64 | ((dtd[7] as u64 >> 4) << 8);
+    let v_blank = dtd[6] as u64 | ((dtd[7] as u64 & 0x0f) << 8);
+    let total = (h_active + h_blank) * (v_active + v_blank);
+    if clock == 0 || total == 0 {
+        return None;
+    }
+    Some(((clock as f64) / (total as f64)).round() as u32)
+}
+
+// the kms connectors, for wayland and the console; modes has no rates, so
+// those come from the edid
+fn read_drm_displays() -> Vec<Display> {
+    let mut connectors: Vec<String> = match fs::read_dir("/sys/class/drm") {
+        Ok(entries) => entries
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .filter(|name| name.starts_with("card") && name.contains('-'))
+            .collect(),
+        Err(_) => return Vec::new(),
+    };
+    connectors.sort();
+
+    connectors
+        .iter()
+        .filter_map(|connector| {
+            let dir = format!("/sys/class/drm/{}", connector);
+            let status = fs::read_to_string(format!("{}/status", dir)).ok()?;
+            if status.trim() != "connected" {
+                return None;
+            }
+            // the first listed mode is the preferred one
+            let mode = fs::read_to_string(format!("{}/modes", dir))
+                .ok()?
+                .lines()
+                .next()?
+                .trim()
+                .to_string();
+            let refresh = fs::read(format!("{}/edid", dir))
+                .ok()
+                .and_then(|edid| parse_edid_refresh(&edid));
+            Some(Display { mode, refresh })
+        })
+        .collect()
+}
+
+pub fn get_displays() -> Vec<Display> {
+    let displays = read_xrandr_displays();
+    if displays.is_empty() {
+        return read_drm_displays();
+    }
+    displays
+}
+
+// first thing that looks like a version number, e.g. "5.2.15" out of
+// "GNU bash, version 5.2.15(1)-release (x86_64-pc-linux-gnu)"
+pub fn parse_version_token(text: &str) -> Option<String> {
+    text.split_whitespace().find_map(|token| {
+        let version: String = token
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == '.')
+            .collect();
+        let version = version.trim_end_matches('.');
+        (version.contains('.') && version.starts_with(|c: char| c.is_ascii_digit()))
+            .then(|| version.to_string())
+    })
+}
+
+// first version number in `program --version`, which for some tools (like
+// pipewire) isn't on the first line
+fn command_version(program: &str) -> Option<String> {
+    let output = Command::new(program)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    parse_version_token(&String::from_utf8_lossy(&output.stdout))
+}
+
+// "bash 5.2.15" from $SHELL, or the bare path when the version can't be found
+pub fn get_shell() -> Option<String> {
+    let path = env::var("SHELL").ok()?;
+    let name = path.rsplit('/').next().unwrap_or(&path).to_string();
+    let exported = match name.as_str() {
+        "bash" => env::var("BASH_VERSION").ok(),
+        "zsh" => env::var("ZSH_VERSION").ok(),
+        _ => None,
+    };
+    match exported
+        .as_deref()
+        .and_then(parse_version_token)
+        .or_else(|| command_version(&path))
+    {
+        Some(version) => Some(format!("{} {}", name, version)),
+        None => Some(path),
+    }
+}
+
+// (XDG_CURRENT_DESKTOP entry, display name, is a full DE, binary that
+// reports the version)
+static DESKTOPS: [(&str, &str, bool, &str); 12] = [
+    ("gnome", "GNOME", true, "gnome-shell"),
+    ("kde", "KDE Plasma", true, "plasmashell"),
+    ("xfce", "Xfce", true, "xfce4-session"),
+    ("x-cinnamon", "Cinnamon", true, "cinnamon"),
+    ("cinnamon", "Cinnamon", true, "cinnamon"),
+    ("mate", "MATE", true, "mate-session"),
+    ("lxqt", "LXQt", true, "lxqt-session"),
+    ("budgie", "Budgie", true, "budgie-desktop"),
+    ("sway", "sway", false, "sway"),
+    ("hyprland", "Hyprland", false, "Hyprland"),
+    ("i3", "i3", false, "i3"),
+    ("river", "river", false, "river"),
+];
+
+// standalone wms don't always set XDG_CURRENT_DESKTOP, but leave a socket
+// variable behind
+static WM_SOCKET_VARS: [(&str, &str); 3] = [
+    ("SWAYSOCK", "sway"),
+    ("HYPRLAND_INSTANCE_SIGNATURE", "hyprland"),
+    ("I3SOCK", "i3"),
+];
+
+// XDG_CURRENT_DESKTOP can be a list like "ubuntu:GNOME", the first entry
+// we know wins, otherwise the raw value is kept without a version
+pub fn get_desktop() -> Option<Desktop> {
+    let current = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+    let known = current
+        .split(':')
+        .map(str::to_lowercase)
+        .chain(
+            WM_SOCKET_VARS
+                .iter()
+                .filter(|(var, _)| env::var_os(var).is_some())
+                .map(|(_, id)| id.to_string()),
+        )
+        .find_map(|id| DESKTOPS.iter().find(|(entry, ..)| *entry == id));
+
+    match known {
+        Some(&(_, name, is_de, binary)) => Some(Desktop {
+            name: name.to_string(),
+            version: command_version(binary),
+            is_de,
+        }),
+        None if !current.is_empty() => Some(Desktop {
+            name: current,
+            version: None,
+            is_de: false,
+        }),
+        None => None,
+    }
+}
+
+// XDG_SESSION_TYPE when it names a display server, otherwise whichever of
+// WAYLAND_DISPLAY and DISPLAY is set (and not empty); a tty session has neither
+pub fn session_type(
+    xdg_session_type: Option<&str>,
+    wayland_display: Option<&str>,
+    display: Option<&str>,
+) -> Option<&'static str> {
+    let is_set = |var: Option<&str>| var.is_some_and(|v| !v.is_empty());
+    match xdg_session_type.map(str::to_lowercase).as_deref() {
+        Some("wayland") => Some("Wayland"),
+        Some("x11") => Some("X11"),
+        _ if is_set(wayland_display) => Some("Wayland"),
+        _ if is_set(display) => Some("X11"),
+        Some("tty") => Some("TTY"),
+        _ => None,
+    }
+}
+
+pub fn get_session_type() -> Option<String> {
+    session_type(
+        env::var("XDG_SESSION_TYPE").ok().as_deref(),
+        env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        env::var("DISPLAY").ok().as_deref(),
+    )
+    .map(str::to_string)
+}
+
+// distinct names with a USER_PROCESS entry in utmp, so one user with a few
+// terminals open counts once; containers usually have no entries at all
+pub fn get_user_count() -> usize {
+    let mut users: Vec<String> = Vec::new();
+    unsafe {
+        setutxent();
+        loop {
+            let entry = getutxent();
+            if entry.is_null() {
+                break;
+            }
+            if (*entry).ut_type != USER_PROCESS {
+                continue;
+            }
+            let name = CStr::from_ptr((*entry).ut_user.as_ptr())
+                .to_string_lossy()
+                .into_owned();
+            if !name.is_empty() && !users.contains(&name) {
+                users.push(name);
+            }
+        }
+        endutxent();
+    }
+    users.len()
+}
+
+// the terminal rfetch runs in, e.g. "pts/0", from whichever std stream is
+// still attached to it
+pub fn get_tty() -> Option<String> {
+    (0..3).find_map(|fd| unsafe {
+        let name = ttyname(fd);
+        if name.is_null() {
+            return None;
+        }
+        let path = CStr::from_ptr(name).to_string_lossy();
+        Some(path.strip_prefix("/dev/").unwrap_or(&path).to_string())
+    })
+}
+
+// (pid 1 comm, init name) for the inits that run under their own name
+static INIT_NAMES: [(&str, &str); 6] = [
+    ("systemd", "systemd"),
+    ("openrc-init", "OpenRC"),
+    ("runit", "runit"),
+    ("s6-svscan", "s6"),
+    ("dinit", "dinit"),
+    ("shepherd", "GNU Shepherd"),
+];
+
+// runtime directories left by inits whose pid 1 is just called "init"
+static INIT_RUNTIME_DIRS: [(&str, &str); 4] = [
+    ("/run/systemd/system", "systemd"),
+    ("/run/openrc", "OpenRC"),
+    ("/run/runit", "runit"),
+    ("/run/s6", "s6"),
+];
+
+// pid 1 name first, then known runtime directories, a plain /sbin/init with
+// an inittab is sysvinit; anything else (e.g. a container entrypoint) is
+// reported by name
+pub fn get_init() -> Option<String> {
+    let comm = fs::read_to_string("/proc/1/comm").ok();
+    let comm = comm.as_deref().map(str::trim);
+    if let Some((_, name)) = INIT_NAMES.iter().find(|(c, _)| Some(*c) == comm) {
+        return Some(name.to_string());
+    }
+    if let Some((_, name)) = INIT_RUNTIME_DIRS
+        .iter()
+        .find(|(dir, _)| fs::metadata(dir).is_ok())
+    {
+        return Some(name.to_string());
+    }
+    match comm {
+        Some("init") if fs::metadata("/etc/inittab").is_ok() => Some("SysVinit".to_string()),
+        Some(comm) if !comm.is_empty() => Some(comm.to_string()),
+        _ => None,
+    }
+}
+
+// LC_ALL overrides everything, LANG is the default for unset categories
+pub fn get_locale() -> Option<String> {
+    ["LC_ALL", "LANG"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.is_empty())
+}
+
+// the zoneinfo path /etc/localtime links to, e.g. Europe/Berlin, or the
+// debian style /etc/timezone file
+pub fn get_timezone() -> Option<String> {
+    if let Ok(target) = fs::read_link("/etc/localtime") {
+        let target = target.to_string_lossy();
+        if let Some((_, zone)) = target.split_once("zoneinfo/") {
+            return Some(zone.to_string());
+        }
+    }
+    let zone = fs::read_to_string("/etc/timezone").ok()?;
+    let zone = zone.trim();
+    (!zone.is_empty()).then(|| zone.to_string())
+}
+
+pub fn get_time() -> Option<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .map(|d| d.as_secs())
+}
+
+// $XDG_CONFIG_HOME, falling back to ~/.config
+fn config_home() -> Option<String> {
+    match env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => Some(dir),
+        _ => Some(format!("{}/.config", env::var("HOME").ok()?)),
+    }
+}
+
+// value of key in [section] of an ini style file like gtk's settings.ini,
+// an empty section matches keys before the first header; simple toml files
+// read the same way
+fn read_ini_value(path: &str, section: &str, key: &str) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    let mut in_section = section.is_empty();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            in_section = name == section;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some((k, v)) = line.split_once('=')
+            && k.trim() == key
+        {
+            let value = v.trim().trim_matches('"');
+            return (!value.is_empty()).then(|| value.to_string());
+        }
+    }
+    None
+}
+
+// e.g. "Adwaita-dark [GTK3/4], Breeze [Qt]"; gtk versions sharing a theme
+// are merged, None on setups without any of these files
+pub fn get_theme() -> Option<String> {
+    let config = config_home()?;
+    let mut themes: Vec<(String, Vec<&str>)> = Vec::new();
+    let mut add = |name: Option<String>, toolkit| {
+        let Some(name) = name else { return };
+        match themes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, toolkits)) => toolkits.push(toolkit),
+            None => themes.push((name, vec![toolkit])),
+        }
+    };
+
+    for version in ["3", "4"] {
+        let path = format!("{}/gtk-{}.0/settings.ini", config, version);
+        add(read_ini_value(&path, "Settings", "gtk-theme-name"), version);
+    }
+    let kdeglobals = format!("{}/kdeglobals", config);
+    add(read_ini_value(&kdeglobals, "KDE", "widgetStyle"), "Qt");
+
+    let shown: Vec<String> = themes
+        .into_iter()
+        .map(|(name, toolkits)| {
+            let gtk: Vec<&str> = toolkits.iter().copied().filter(|t| *t != "Qt").collect();
+            let mut labels = Vec::new();
+            if !gtk.is_empty() {
+                labels.push(format!("GTK{}", gtk.join("/")));
+            }
+            if toolkits.contains(&"Qt") {
+                labels.push("Qt".to_string());
+            }
+            format!("{} [{}]", name, labels.join(", "))
+        })
+        .collect();
+    (!shown.is_empty()).then(|| shown.join(", "))
+}
+
+// the first gtk settings.ini (3, then 4) that sets key
+fn read_gtk_setting(key: &str) -> Option<String> {
+    let config = config_home()?;
+    ["3", "4"].iter().find_map(|version| {
+        let path = format!("{}/gtk-{}.0/settings.ini", config, version);
+        read_ini_value(&path, "Settings", key)
+    })
+}
+
+// gtk's setting, then plasma's
+pub fn get_icon_theme() -> Option<String> {
+    read_gtk_setting("gtk-icon-theme-name").or_else(|| {
+        let kdeglobals = format!("{}/kdeglobals", config_home()?);
+        read_ini_value(&kdeglobals, "Icons", "Theme")
+    })
+}
+
+// gtk's setting, then the theme ~/.icons/default inherits, which is what X
+// cursors fall back to
+pub fn get_cursor_theme() -> Option<String> {
+    read_gtk_setting("gtk-cursor-theme-name").or_else(|| {
+        let index = format!("{}/.icons/default/index.theme", env::var("HOME").ok()?);
+        read_ini_value(&index, "Icon Theme", "Inherits")
+    })
+}
+
+// $XDG_RUNTIME_DIR, or where systemd-logind would have put it
+fn runtime_dir() -> String {
+    match env::var("XDG_RUNTIME_DIR") {
+        Ok(dir) if !dir.is_empty() => dir,
+        _ => format!("/run/user/{}", unsafe { libc::getuid() }),
+    }
+}
+
+// "--- no soundcards ---" when alsa has nothing registered
+fn has_alsa_cards() -> bool {
+    fs::read_to_string("/proc/asound/cards").is_ok_and(|cards| {
+        cards
+            .lines()
+            .any(|l| l.trim_start().starts_with(char::is_numeric))
+    })
+}
+
+// looks for the servers' sockets instead of asking them; pipewire-pulse also
+// listens on pulse/native so pipewire is checked first. only the detected
+// server is run, for its version
+pub fn get_sound_server() -> Option<String> {
+    let runtime = runtime_dir();
+    let exists = |path: &str| fs::metadata(path).is_ok();
+    let uid = unsafe { libc::getuid() };
+    let jack = fs::read_dir("/dev/shm").is_ok_and(|entries| {
+        entries.flatten().any(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            name == format!("jack-{}", uid) || name.starts_with("jack_default")
+        })
+    });
+
+    let (name, binary) = if exists(&format!("{}/pipewire-0", runtime)) {
+        ("PipeWire", Some("pipewire"))
+    } else if exists(&format!("{}/pulse/native", runtime)) {
+        ("PulseAudio", Some("pulseaudio"))
+    } else if jack {
+        ("JACK", None)
+    } else if has_alsa_cards() {
+        ("ALSA", None)
+    } else {
+        return None;
+    };
+    match binary.and_then(command_version) {
+        Some(version) => Some(format!("{} {}", name, version)),
+        None => Some(name.to_string()),
+    }
+}
+
+// $VISUAL wins over $EDITOR, e.g. "nvim" for /usr/bin/nvim
+pub fn get_editor() -> Option<String> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.trim().is_empty())?;
+    let program = editor.split_whitespace().next()?;
+    Some(program.rsplit('/').next().unwrap_or(program).to_string())
+}
+
+// "<family> <size>" with whichever parts are set
+fn join_font(family: Option<String>, size: Option<String>) -> Option<String> {
+    match (family, size) {
+        (Some(family), Some(size)) => Some(format!("{} {}", family, size)),
+        (family, _) => family,
+    }
+}
+
+// key value lines separated by whitespace, like kitty.conf
+fn read_space_separated_value(path: &str, key: &str) -> Option<String> {
+    let text = fs::read_to_string(path).ok()?;
+    text.lines().find_map(|line| {
+        let (k, v) = line.trim().split_once(char::is_whitespace)?;
+        (k == key).then(|| v.trim().to_string())
+    })
+}
+
+// only terminals with a config file we can read are known; the running one is
+// recognised from the variables it exports
+pub fn get_terminal_font() -> Option<String> {
+    let config = config_home()?;
+    let term = env::var("TERM").unwrap_or_default();
+
+    if env::var_os("KITTY_WINDOW_ID").is_some() || term == "xterm-kitty" {
+        let path = format!("{}/kitty/kitty.conf", config);
+        return join_font(
+            read_space_separated_value(&path, "font_family"),
+            read_space_separated_value(&path, "font_size"),
+        );
+    }
+    if env::var_os("ALACRITTY_SOCKET").is_some() || term == "alacritty" {
+        let path = format!("{}/alacritty/alacritty.toml", config);
+        return join_font(
+            read_ini_value(&path, "font.normal", "family"),
+            read_ini_value(&path, "font", "size"),
+        );
+    }
+    if term.starts_with("foot") {
+        // font=Family:size=11 in [main], or before any section
+        let path = format!("{}/foot/foot.ini", config);
+        let font =
+            read_ini_value(&path, "main", "font").or_else(|| read_ini_value(&path, "", "font"))?;
+        let mut parts = font.split(':');
+        let family = parts.next().map(|f| f.trim().to_string());
+        let size = parts
+            .find_map(|p| p.strip_prefix("size="))
+            .map(str::to_string);
+        return join_font(family, size);
+    }
+    None
+}
+
+// uname's machine, e.g. x86_64 or i686
+pub fn get_arch() -> Option<String> {
+    unsafe {
+        let mut uts: utsname = std::mem::zeroed();
+        if libc::uname(&mut uts) == 0 {
+            Some(
+                CStr::from_ptr(uts.machine.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        } else {
+            None
+        }
+    }
+}
+
+pub fn get_kernel() -> Option<Kernel> {
+    unsafe {
+        let mut uts: utsname = std::mem::zeroed();
+        if libc::uname(&mut uts) == 0 {
+            Some(Kernel {
+                name: CStr::from_ptr(uts.sysname.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+                release: CStr::from_ptr(uts.release.as_ptr())
+                    .to_string_lossy()
+                    .into_owned(),
+            })
+        } else {
+            None
+        }
+    }
+}
+
+// virtual interfaces that are never the interesting address
+static SKIPPED_IFACE_PREFIXES: [&str; 6] = ["lo", "docker", "br-", "virbr", "veth", "vnet"];
+
+fn is_skipped_iface(name: &str) -> bool {
+    SKIPPED_IFACE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+}
+
+fn is_usable_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => !v4.is_loopback() && !v4.is_link_local(),
+        // fe80::/10 is link-local
+        IpAddr::V6(v6) => !v6.is_loopback() && (v6.segments()[0] & 0xffc0) != 0xfe80,
+    }
+}
+
+fn list_interface_addrs() -> Vec<LocalIp> {
+    let mut addrs = Vec::new();
+    unsafe {
+        let mut ifap: *mut ifaddrs = ptr::null_mut();
+        if getifaddrs(&mut ifap) != 0 {
+            return addrs;
+        }
+
+        let mut ptr_ifap = ifap;
+        while !ptr_ifap.is_null() {
+            let ifa = &*ptr_ifap;
+            ptr_ifap = ifa.ifa_next;
+            if ifa.ifa_addr.is_null() || ifa.ifa_name.is_null() {
+                continue;
+            }
+            let addr = match (*ifa.ifa_addr).sa_family as i32 {
+                AF_INET => {
+                    let sa = &*(ifa.ifa_addr as *const sockaddr_in);
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(sa.sin_addr.s_addr)))
+                }
+                AF_INET6 => {
+                    let sa = &*(ifa.ifa_addr as *const sockaddr_in6);
+                    IpAddr::V6(Ipv6Addr::from(sa.sin6_addr.s6_addr))
+                }
+                _ => continue,
+            };
+            addrs.push(LocalIp {
+                addr,
+                iface: CStr::from_ptr(ifa.ifa_name).to_string_lossy().to_string(),
+            });
+        }
+
+        freeifaddrs(ifap);
+    }
+    addrs
+}
+
+// first usable address, ipv4 preferred, either on the pinned interface or on
+// any non-virtual one
+pub fn get_local_ip(iface: Option<&str>) -> Option<LocalIp> {
+    let candidates: Vec<LocalIp> = list_interface_addrs()
+        .into_iter()
+        .filter(|ip| match iface {
+            Some(name) => ip.iface == name,
+            None => !is_skipped_iface(&ip.iface),
+        })
+        .filter(|ip| is_usable_ip(&ip.addr))
+        .collect();
+
+    let index = candidates
+        .iter()
+        .position(|ip| ip.addr.is_ipv4())
+        .unwrap_or(0);
+    candidates.into_iter().nth(index)
+}
+
+// the default route with the lowest metric, from /proc/net/route where the
+// addresses are hex in network byte order read as a little endian number
+pub fn get_gateway() -> Option<Ipv4Addr> {
+    let routes = fs::read_to_string("/proc/net/route").ok()?;
+    routes
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let cols: Vec<&str> = line.split_whitespace().collect();
+            let (destination, gateway, flags, metric, mask) = (
+                cols.get(1)?,
+                cols.get(2)?,
+                cols.get(3)?,
+                cols.get(6)?,
+                cols.get(7)?,
+            );
+            let flags = u32::from_str_radix(flags, 16).ok()?;
+            // RTF_UP and RTF_GATEWAY
+            if *destination != "00000000" || *mask != "00000000" || flags & 0x3 != 0x3 {
+                return None;
+            }
+            let gateway = u32::from_str_radix(gateway, 16).ok()?;
+            Some((
+                metric.parse::<u32>().ok()?,
+                Ipv4Addr::from(gateway.to_le_bytes()),
+            ))
+        })
+        .min_by_key(|&(metric, _)| metric)
+        .map(|(_, gateway)| gateway)
+}
+
+fn resolv_nameservers(path: &str) -> Vec<String> {
+    fs::read_to_string(path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            (words.next()? == "nameserver").then_some(words.next()?.to_string())
+        })
+        .collect()
+}
+
+pub static RESOLVED_STUB: &str = "127.0.0.53";
+
+pub fn get_dns() -> Option<Dns> {
+    let servers = resolv_nameservers("/etc/resolv.conf");
+    if servers.is_empty() {
+        return None;
+    }
+    if servers.iter().any(|s| s != RESOLVED_STUB) {
+        return Some(Dns {
+            servers,
+            stub: false,
+        });
+    }
+    let upstream = resolv_nameservers("/run/systemd/resolve/resolv.conf");
+    Some(Dns {
+        servers: if upstream.is_empty() {
+            servers
+        } else {
+            upstream
+        },
+        stub: true,
+    })
+}
+
+// wireless interfaces and their link quality from /proc/net/wireless, whose
+// rows (after two header lines) look like
+//     wlan0: 0000   70.  -40.  -256        0      0      0      0      0        0
+// with the quality out of 70, the scale nearly every driver uses
+fn parse_proc_wireless(text: &str) -> Vec<(String, Option<u8>)> {
+    text.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (iface, rest) = line.split_once(':')?;
+            let quality = rest.split_whitespace().nth(1)?.trim_end_matches('.');
+            let signal = quality
+                .parse::<f64>()
+                .ok()
+                .map(|q| (q * 100.0 / 70.0).clamp(0.0, 100.0).round() as u8);
+            Some((iface.trim().to_string(), signal))
+        })
+        .collect()
+}
+
+// the ssid from wireless-tools' iwgetid, or iw on systems that only have that
+fn wifi_ssid(iface: &str) -> Option<String> {
+    let run = |program: &str, args: &[&str]| {
+        let output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()
+            .ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let ssid = run("iwgetid", &["-r", iface]).or_else(|| {
+        let link = run("iw", &["dev", iface, "link"])?;
+        link.lines()
+            .find_map(|line| line.trim().strip_prefix("SSID: ").map(str::to_string))
+    })?;
+    let ssid = ssid.trim();
+    (!ssid.is_empty()).then(|| ssid.to_string())
+}
+
+// the first wireless interface that is associated, which is when the kernel
+// reports its operstate as up (it stays dormant until then)
+pub fn get_wifi() -> Option<Wifi> {
+    let wireless = fs::read_to_string("/proc/net/wireless").ok()?;
+    parse_proc_wireless(&wireless)
+        .into_iter()
+        .find(|(iface, _)| {
+            fs::read_to_string(format!("/sys/class/net/{}/operstate", iface))
+                .is_ok_and(|state| state.trim() == "up")
+        })
+        .map(|(iface, signal)| Wifi {
+            ssid: wifi_ssid(&iface),
+            iface,
+            signal,
+        })
+}
+
+// hardware address of the interface get_local_ip would report; interfaces
+// without one (tun, wireguard) read as all zeros
+pub fn get_mac(iface: Option<&str>) -> Option<String> {
+    let iface = get_local_ip(iface)?.iface;
+    let mac = fs::read_to_string(format!("/sys/class/net/{}/address", iface)).ok()?;
+    let mac = mac.trim();
+    (!mac.is_empty() && mac.split(':').any(|octet| octet != "00")).then(|| mac.to_string())
+}
+
+pub const DEFAULT_NET_SAMPLE_MS: u64 = 200;
+
+fn read_net_bytes(iface: &str) -> Option<(u64, u64)> {
+    let read = |name: &str| {
+        fs::read_to_string(format!("/sys/class/net/{}/statistics/{}", iface, name))
+            .ok()?
+            .trim()
+            .parse::<u64>()
+            .ok()
+    };
+    Some((read("rx_bytes")?, read("tx_bytes")?))
+}
+
+// rx/tx rates of the interface get_local_ip would report, from two reads of
+// its byte counters interval apart
+pub fn get_net_speed(iface: Option<&str>, interval: Duration) -> Option<NetSpeed> {
+    let iface = get_local_ip(iface)?.iface;
+    let before = read_net_bytes(&iface)?;
+    thread::sleep(interval);
+    let after = read_net_bytes(&iface)?;
+    let ms = interval.as_millis().max(1) as u64;
+    let rate = |before: u64, after: u64| after.saturating_sub(before) * 1000 / ms;
+    let (rx, tx) = (rate(before.0, after.0), rate(before.1, after.1));
+    (rx > 0 || tx > 0).then_some(NetSpeed { iface, rx, tx })
+}
+
+static PUBLIC_IP_HOST: &str = "api.ipify.org";
+const PUBLIC_IP_TIMEOUT: Duration = Duration::from_secs(2);
+
+fn fetch_public_ip() -> Option<IpAddr> {
+    let addr = (PUBLIC_IP_HOST, 80).to_socket_addrs().ok()?.next()?;
+    let mut stream = TcpStream::connect_timeout(&addr, PUBLIC_IP_TIMEOUT).ok()?;
+    stream.set_read_timeout(Some(PUBLIC_IP_TIMEOUT)).ok()?;
+    stream.set_write_timeout(Some(PUBLIC_IP_TIMEOUT)).ok()?;
+
+    let request = format!(
+        "GET / HTTP/1.1\r\nHost: {}\r\nUser-Agent: rfetch\r\nConnection: close\r\n\r\n",
+        PUBLIC_IP_HOST
+    );
+    stream.write_all(request.as_bytes()).ok()?;
+
+    let mut response = String::new();
+    stream.read_to_string(&mut response).ok()?;
+    let (head, body) = response.split_once("\r\n\r\n")?;
+    if head.split_whitespace().nth(1) != Some("200") {
+        return None;
+    }
+    body.trim().parse().ok()
+}
+
+// the lookup runs on its own thread so a hanging dns query can't hold up the
+// rest of the output past the timeout
+pub fn get_public_ip() -> Option<IpAddr> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(fetch_public_ip());
+    });
+    rx.recv_timeout(PUBLIC_IP_TIMEOUT * 2).ok().flatten()
+}
+
+// numeric entries in /proc are processes, their threads only show up under
+// /proc/<pid>/task
+pub fn get_process_count() -> Option<usize> {
+    let entries = fs::read_dir("/proc").ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|e| {
+                e.file_name()
+                    .to_str()
+                    .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()))
+            })
+            .count(),
+    )
+}
+
+// 1, 5 and 15 minute load averages
+pub fn get_loadavg() -> Option<[f64; 3]> {
+    let loadavg = fs::read_to_string("/proc/loadavg").ok()?;
+    let mut fields = loadavg.split_whitespace().map(|v| v.parse::<f64>().ok());
+    Some([fields.next()??, fields.next()??, fields.next()??])
+}
+
+pub fn get_uptime() -> Option<Duration> {
+    unsafe {
+        let mut info: sysinfo = std::mem::zeroed();
+        if libc::sysinfo(&mut info) == 0 {
+            Some(Duration::from_secs(info.uptime as u64))
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn os_release_values_may_be_quoted_or_bare() {
+        let os = parse_os_release(
+            "PRETTY_NAME=\"Debian GNU/Linux 12 (bookworm)\"\nID=debian\nID_LIKE='rhel fedora'\n",
+        );
+        assert_eq!(os.name, "Debian GNU/Linux 12 (bookworm)");
+        assert_eq!(os.id, "debian");
+        assert_eq!(os.id_like, "rhel fedora");
+        let os = parse_os_release("PRETTY_NAME=\"Say \\\"hi\\\" \\$HOME\"\n");
+        assert_eq!(os.name, "Say \"hi\" $HOME");
+    }
+
+    #[test]
+    fn os_release_skips_comments_and_blank_lines() {
+        let os = parse_os_release(
+            "# PRETTY_NAME=\"Nope\"\n\n   \nNAME=Alpine\n  # ID=nope\nID=alpine\n",
+        );
+        assert_eq!(os.name, "Alpine");
+        assert_eq!(os.id, "alpine");
+    }
+
+    #[test]
+    fn os_release_without_pretty_name_uses_name_and_version() {
+        let os = parse_os_release("NAME=\"Tiny Linux\"\nVERSION=\"1.2 (Quokka)\"\n");
+        assert_eq!(os.name, "Tiny Linux 1.2 (Quokka)");
+        assert_eq!(parse_os_release("NAME=Tiny\n").name, "Tiny");
+        assert_eq!(parse_os_release("ID=tiny\n").name, "Unknown OS");
+        assert_eq!(
+            parse_os_release("PRETTY_NAME=\"\"\nNAME=Tiny\n").name,
+            "Tiny"
+        );
+    }
+
+    #[test]
+    fn session_type_prefers_xdg_session_type() {
+        assert_eq!(
+            session_type(Some("wayland"), None, Some(":0")),
+            Some("Wayland")
+        );
+        assert_eq!(
+            session_type(Some("X11"), Some("wayland-0"), None),
+            Some("X11")
+        );
+    }
+
+    #[test]
+    fn session_type_falls_back_to_wayland_display() {
+        assert_eq!(
+            session_type(None, Some("wayland-0"), Some(":0")),
+            Some("Wayland")
+        );
+        assert_eq!(
+            session_type(Some("unspecified"), Some("wayland-0"), None),
+            Some("Wayland")
+        );
+    }
+
+    #[test]
+    fn session_type_falls_back_to_display() {
+        assert_eq!(session_type(None, Some(""), Some(":0")), Some("X11"));
+        assert_eq!(session_type(Some("tty"), None, Some(":1")), Some("X11"));
+    }
+
+    #[test]
+    fn session_type_without_a_display_server() {
+        assert_eq!(session_type(Some("tty"), None, None), Some("TTY"));
+        assert_eq!(session_type(None, None, Some("")), None);
+    }
+
+    #[test]
+    fn arch_matches_the_build_target() {
+        let arch = get_arch().expect("uname works");
+        // uname is more specific than rust for the 32 bit targets
+        match std::env::consts::ARCH {
+            "x86" => assert!(["i386", "i486", "i586", "i686"].contains(&arch.as_str())),
+            "arm" => assert!(arch.starts_with("arm")),
+            target => assert_eq!(arch, target),
+        }
+    }
+
+    #[test]
+    fn container_root_is_a_disk_but_other_overlays_are_not() {
+        assert!(is_disk_mount("/", "overlay"));
+        assert!(is_disk_mount("/home", "ext4"));
+        assert!(!is_disk_mount(
+            "/var/lib/docker/overlay2/abc/merged",
+            "overlay"
+        ));
+        assert!(!is_disk_mount("/proc", "proc"));
+    }
+
+    #[test]
+    fn installed_ram_prefers_the_smbios_size() {
+        let ram = RamHardware {
+            kind: "DDR4".to_string(),
+            speed: Some(3200),
+            populated: 2,
+            slots: 4,
+            size: Some(16 << 30),
+        };
+        let memory = Some(Usage {
+            used: 1 << 30,
+            total: 15 << 30,
+            avail: None,
+        });
+        let installed = get_ram_installed(Some(&ram), memory).unwrap();
+        assert_eq!((installed.bytes, installed.from_smbios), (16 << 30, true));
+        let installed = get_ram_installed(None, memory).unwrap();
+        assert_eq!((installed.bytes, installed.from_smbios), (15 << 30, false));
+        assert!(get_ram_installed(None, None).is_none());
+    }
+
+    #[test]
+    fn wsl_version_from_proc_version() {
+        let wsl2 = "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@941d701f84f1) (gcc (GCC) 11.2.0) #1 SMP";
+        assert_eq!(parse_wsl_version(wsl2), Some(2));
+        let wsl1 = "Linux version 4.4.0-19041-Microsoft (Microsoft@Microsoft.com) (gcc version 5.4.0) #1237-Microsoft";
+        assert_eq!(parse_wsl_version(wsl1), Some(1));
+        let native = "Linux version 6.1.0-18-amd64 (debian-kernel@lists.debian.org) (gcc-12 (Debian 12.2.0-14) 12.2.0)";
+        assert_eq!(parse_wsl_version(native), None);
+        assert_eq!(parse_wsl_version(""), None);
+    }
+
+    #[test]
+    fn statvfs_usage_leaves_the_root_reserve_out_like_df() {
+        // 100 blocks, 30 free of which 20 are available to users
+        let (used, avail, total) = usage_from(100, 30, 20, 4096);
+        assert_eq!((used, avail, total), (70 * 4096, 20 * 4096, 100 * 4096));
+        let usage = Usage {
+            used,
+            total,
+            avail: Some(avail),
+        };
+        // 70 of 90 rounded up, not 70 of 100
+        assert_eq!(usage.percent(), 78);
+    }
+
+    #[test]
+    fn statvfs_usage_saturates_instead_of_wrapping() {
+        let (used, _, total) = usage_from(u64::MAX / 2, 0, 0, 4096);
+        assert_eq!((used, total), (u64::MAX, u64::MAX));
+        let (used, avail, total) = usage_from(u64::MAX / 2, u64::MAX / 4, u64::MAX / 4, 4096);
+        assert_eq!((used, avail, total), (u64::MAX, u64::MAX, u64::MAX));
+    }
+
+    #[test]
+    fn statvfs_usage_clamps_more_free_blocks_than_there_are() {
+        assert_eq!(usage_from(100, 500, 500, 1024), (0, 100 * 1024, 100 * 1024));
+        assert_eq!(
+            usage_from(100, 50, 80, 1024),
+            (50 * 1024, 50 * 1024, 100 * 1024)
+        );
+    }
+
+    #[test]
+    fn proc_stat_counts_idle_and_iowait_as_not_busy() {
+        // user nice system idle iowait irq softirq steal
+        let stat = "cpu  100 0 50 800 40 5 5 0 0 0\ncpu0 100 0 50 800 40 5 5 0 0 0\nintr 1 2 3\n";
+        assert_eq!(parse_proc_stat(stat), vec![(160, 1000), (160, 1000)]);
+    }
+
+    #[test]
+    fn cpu_usage_is_the_busy_share_of_the_delta() {
+        let before = parse_proc_stat("cpu  100 0 50 800 40 5 5 0\n");
+        let after = parse_proc_stat("cpu  130 0 60 850 50 5 5 0\n");
+        // 40 busy of 100 jiffies
+        assert_eq!(cpu_usage_between(&before, &after), vec![40]);
+    }
+
+    #[test]
+    fn cpu_usage_of_an_unchanged_snapshot_is_zero() {
+        let stat = parse_proc_stat("cpu  100 0 50 800 40 5 5 0\n");
+        assert_eq!(cpu_usage_between(&stat, &stat), vec![0]);
+    }
+
+    #[test]
+    fn truncated_proc_stat_lines_do_not_panic() {
+        assert_eq!(
+            parse_proc_stat("cpu  10 20\ncpu1\n"),
+            vec![(30, 30), (0, 0)]
+        );
+        assert_eq!(parse_proc_stat(""), vec![]);
+    }
+
+    #[test]
+    fn surface_and_metal_instances_are_not_vms() {
+        let surface = (
+            "Microsoft Corporation",
+            "Surface Pro 7",
+            "Microsoft Corporation",
+        );
+        assert_eq!(
+            hypervisor_from_dmi(surface.0, surface.1, surface.2, None),
+            None
+        );
+        assert_eq!(
+            hypervisor_from_dmi("Microsoft Corporation", "Virtual Machine", "", Some(true)),
+            Some("Hyper-V")
+        );
+        assert_eq!(
+            hypervisor_from_dmi("Amazon EC2", "c5.metal", "Amazon EC2", Some(false)),
+            None
+        );
+        assert_eq!(
+            hypervisor_from_dmi("Amazon EC2", "c5.large", "Amazon EC2", Some(true)),
+            Some("Amazon EC2")
+        );
+    }
+}
@@ -0,0 +1,45 @@
+// --theme presets: logo, separator and label colors that go together
+use crate::color;
+
+pub struct Theme {
+    pub name: &'static str,
+    pub logo: (u8, u8, u8),
+    pub separator: (u8, u8, u8),
+    pub label: (u8, u8, u8),
+}
+
+pub static THEMES: &[Theme] = &[
+    Theme {
+        name: "nord",
+        logo: (0x88, 0xc0, 0xd0),
+        separator: (0x4c, 0x56, 0x6a),
+        label: (0x81, 0xa1, 0xc1),
+    },
+    Theme {
+        name: "gruvbox",
+        logo: (0xfe, 0x80, 0x19),
+        separator: (0x92, 0x83, 0x74),
+        label: (0xfa, 0xbd, 0x2f),
+    },
+    Theme {
+        name: "dracula",
+        logo: (0xbd, 0x93, 0xf9),
+        separator: (0x62, 0x72, 0xa4),
+        label: (0xff, 0x79, 0xc6),
+    },
+];
+
+pub fn find(name: &str) -> Result<&'static Theme, String> {
+    THEMES
+        .iter()
+        .find(|theme| theme.name == name.to_lowercase())
+        .ok_or_else(|| {
+            let names: Vec<&str> = THEMES.iter().map(|theme| theme.name).collect();
+            format!("unknown theme '{}' (available: {})", name, names.join(", "))
+        })
+}
+
+// SGR parameters, truecolor or the nearest 256 color like --color's rgb forms
+pub fn sgr((r, g, b): (u8, u8, u8)) -> String {
+    color::rgb_sgr(r, g, b)
+}